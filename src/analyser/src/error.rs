@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Clone, Error, Debug)]
@@ -42,9 +43,78 @@ pub enum SemanticError {
 
 }
 
+// Suspicious code that is still valid, reported without failing the compilation
+#[derive(Clone, Error, Debug)]
+pub enum SemanticWarning {
+    #[error("[Semantic Warning] Unreachable Code[{id:?}] at line {line:?}: {message:?}")]
+    UnreachableCode{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Unused Variable[{id:?}] at line {line:?}: {message:?}")]
+    UnusedVariable{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Unused Parameter[{id:?}] at line {line:?}: {message:?}")]
+    UnusedParameter{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Unused Function[{id:?}] at line {line:?}: {message:?}")]
+    UnusedFunction{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Shadowing[{id:?}] at line {line:?}: {message:?}")]
+    Shadowing{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Unused Assignment[{id:?}] at line {line:?}: {message:?}")]
+    UnusedAssignment{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Uninitialized Variable[{id:?}] at line {line:?}: {message:?}")]
+    Uninitialized{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+    #[error("[Semantic Warning] Constant Overflow[{id:?}] at line {line:?}: {message:?}")]
+    Overflow{
+        id: usize,
+        message: String,
+        line: usize,
+    },
+}
+
+// Names of the warnings on the command line, in the order of their ids
+pub const LINTS: [&str; 8] = [
+    "unreachable-code",
+    "unused-variable",
+    "unused-parameter",
+    "unused-function",
+    "shadowing",
+    "unused-assignment",
+    "uninitialized",
+    "overflow",
+];
+
 pub struct SemanticErrorManager {
     cnt: usize,
     errors: Vec<SemanticError>,
+    warnings: Vec<SemanticWarning>,
+    // Lints whose warnings are dropped, and whether warnings fail the compilation
+    allowed: HashSet<&'static str>,
+    werror: bool,
     line: usize,
 }
 
@@ -53,6 +123,9 @@ impl SemanticErrorManager {
         SemanticErrorManager {
             cnt: 0,
             errors: Vec::new(),
+            warnings: Vec::new(),
+            allowed: HashSet::new(),
+            werror: false,
             line: 0,
         }
     }
@@ -63,10 +136,75 @@ impl SemanticErrorManager {
         self.errors.push(error);
     }
 
+    // Errors about a function as a whole, reported at its declaration
+    pub fn add_error_at(&mut self, mut error: SemanticError, line: usize) {
+        self.cnt += 1;
+        error.update_line(line);
+        self.errors.push(error);
+    }
+
     pub fn get_errors(&self) -> &Vec<SemanticError> {
         &self.errors
     }
 
+    pub fn add_warning(&mut self, warning: SemanticWarning) {
+        self.add_warning_at(warning, self.line);
+    }
+
+    // Warnings about a declaration are found when its scope ends, long
+    // after the line of the declaration. The bodies of generic functions
+    // are checked once per instantiation, so repeated warnings are dropped.
+    pub fn add_warning_at(&mut self, mut warning: SemanticWarning, line: usize) {
+        if self.allowed.contains(warning.lint()) {
+            return;
+        }
+        warning.update_line(line);
+        if self.warnings.iter().all(|w| w.to_string() != warning.to_string()) {
+            self.warnings.push(warning);
+        }
+    }
+
+    pub fn get_warnings(&self) -> &Vec<SemanticWarning> {
+        &self.warnings
+    }
+
+    // Warnings are found out of order, they are listed by line
+    pub fn sort_warnings(&mut self) {
+        self.warnings.sort_by_key(|w| w.line());
+    }
+
+    // `-W <lint>` and `-A <lint>`, where `all` names every lint and
+    // `-W error` (i.e. `-Werror`) turns warnings into errors
+    pub fn set_lint(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        let lints: Vec<&'static str> = match name {
+            "error" => {
+                self.werror = enabled;
+                return Ok(());
+            }
+            "all" => LINTS.to_vec(),
+            _ => match LINTS.iter().find(|lint| **lint == name) {
+                Some(lint) => vec![*lint],
+                None => return Err(format!("Unknown warning {}, expected one of all, error, {}", name, LINTS.join(", "))),
+            },
+        };
+        for lint in lints {
+            if enabled {
+                self.allowed.remove(lint);
+            } else {
+                self.allowed.insert(lint);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_werror(&self) -> bool {
+        self.werror
+    }
+
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
     pub fn has_error(&self) -> bool {
         !self.errors.is_empty()
     }
@@ -80,6 +218,48 @@ impl SemanticErrorManager {
     }
 }
 
+impl SemanticWarning {
+    // Name of the lint that controls this warning
+    pub fn lint(&self) -> &'static str {
+        match self {
+            SemanticWarning::UnreachableCode{id, ..}
+            | SemanticWarning::UnusedVariable{id, ..}
+            | SemanticWarning::UnusedParameter{id, ..}
+            | SemanticWarning::UnusedFunction{id, ..}
+            | SemanticWarning::Shadowing{id, ..}
+            | SemanticWarning::UnusedAssignment{id, ..}
+            | SemanticWarning::Uninitialized{id, ..}
+            | SemanticWarning::Overflow{id, ..} => LINTS[id - 1],
+        }
+    }
+
+    fn line(&self) -> usize {
+        match self {
+            SemanticWarning::UnreachableCode{line, ..}
+            | SemanticWarning::UnusedVariable{line, ..}
+            | SemanticWarning::UnusedParameter{line, ..}
+            | SemanticWarning::UnusedFunction{line, ..}
+            | SemanticWarning::Shadowing{line, ..}
+            | SemanticWarning::UnusedAssignment{line, ..}
+            | SemanticWarning::Uninitialized{line, ..}
+            | SemanticWarning::Overflow{line, ..} => *line,
+        }
+    }
+
+    fn update_line(&mut self, line: usize) {
+        match self {
+            SemanticWarning::UnreachableCode{line: ref mut l, ..}
+            | SemanticWarning::UnusedVariable{line: ref mut l, ..}
+            | SemanticWarning::UnusedParameter{line: ref mut l, ..}
+            | SemanticWarning::UnusedFunction{line: ref mut l, ..}
+            | SemanticWarning::Shadowing{line: ref mut l, ..}
+            | SemanticWarning::UnusedAssignment{line: ref mut l, ..}
+            | SemanticWarning::Uninitialized{line: ref mut l, ..}
+            | SemanticWarning::Overflow{line: ref mut l, ..} => *l = line,
+        }
+    }
+}
+
 impl SemanticError {
     fn update_line(&mut self, line: usize) {
         match self {
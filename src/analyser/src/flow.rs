@@ -0,0 +1,111 @@
+use spl_ast::tree::*;
+use spl_lexer::tokens::Span;
+
+/*
+    Control flow of a function body. Bodies are structured, so the flow is
+    followed through the statements instead of building a graph: each
+    statement either falls through to the next one or leaves the sequence,
+    and whatever follows a leaving statement is unreachable.
+*/
+
+// How control leaves a statement
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Exit {
+    Falls,
+    Return,
+    Break,
+    Continue,
+    // A loop without a condition or break, which never ends
+    Forever,
+    // An if-else whose branches all leave, in different ways
+    Branches,
+}
+
+impl Exit {
+    fn describe(&self) -> &'static str {
+        match self {
+            Exit::Falls => "",
+            Exit::Return => "return",
+            Exit::Break => "break",
+            Exit::Continue => "continue",
+            Exit::Forever => "an endless loop",
+            Exit::Branches => "an if-else whose branches all leave",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FlowChecker {
+    // Enclosing loops, innermost last: the label and whether a break reaches it
+    loops: Vec<(Option<String>, bool)>,
+    // First unreachable statement of each sequence, and what comes before it
+    pub unreachable: Vec<(Span, &'static str)>,
+}
+
+impl FlowChecker {
+    // Whether control can reach the end of the body, i.e. the function
+    // falls off its end without returning
+    pub fn check_function(&mut self, body: &Body) -> bool {
+        self.check_body(body) == Exit::Falls
+    }
+
+    fn check_body(&mut self, body: &Body) -> Exit {
+        let Body::Body(exprs) = body else {
+            return Exit::Falls;
+        };
+        let mut exit = Exit::Falls;
+        for expr in exprs {
+            if exit != Exit::Falls {
+                if let Some(span) = expr.span() {
+                    self.unreachable.push((span.clone(), exit.describe()));
+                }
+                break;
+            }
+            exit = self.check_expr(expr);
+        }
+        exit
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Exit {
+        match expr {
+            Expr::Return(..) => Exit::Return,
+            Expr::Break(label, _) => {
+                let target = match label {
+                    Some(label) => self.loops.iter_mut().rev().find(|(l, _)| l.as_ref() == Some(label)),
+                    None => self.loops.last_mut(),
+                };
+                if let Some((_, broken)) = target {
+                    *broken = true;
+                }
+                Exit::Break
+            }
+            Expr::Continue(..) => Exit::Continue,
+            Expr::If(If::IfExpr(_, body), _) => {
+                self.check_body(body);
+                Exit::Falls
+            }
+            Expr::If(If::IfElseExpr(_, then, otherwise), _) => {
+                match (self.check_body(then), self.check_body(otherwise)) {
+                    (Exit::Falls, _) | (_, Exit::Falls) => Exit::Falls,
+                    (a, b) if a == b => a,
+                    _ => Exit::Branches,
+                }
+            }
+            Expr::Loop(Loop::WhileExpr(cond, body), label, _) => self.check_loop(cond, body, label),
+            Expr::Loop(Loop::ForExpr(_, cond, _, body), label, _) => self.check_loop(cond, body, label),
+            Expr::Body(body, _) => self.check_body(body),
+            _ => Exit::Falls,
+        }
+    }
+
+    // A loop ends unless its condition is always true and nothing breaks it
+    fn check_loop(&mut self, cond: &CondExpr, body: &Body, label: &Option<String>) -> Exit {
+        self.loops.push((label.clone(), false));
+        self.check_body(body);
+        let (_, broken) = self.loops.pop().unwrap();
+        match cond {
+            CondExpr::Bool(true) if !broken => Exit::Forever,
+            _ => Exit::Falls,
+        }
+    }
+}
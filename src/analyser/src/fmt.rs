@@ -2,6 +2,7 @@ use std::fmt::{Display, Result};
 use crate::symbol::{Symbol, VarType, BasicType};
 use crate::table::ScopeTable;
 use crate::stack::ScopeStack;
+use spl_ast::tree::IntType;
 
 impl<T> Display for Symbol<T> 
 where 
@@ -18,13 +19,41 @@ impl Display for VarType {
             VarType::Primitive(basic_type) => {
                 write!(f, "Primitive: type: {:?}", basic_type)
             },
-            VarType::Array(array_type) => {
-                write!(f, "Array: {:?}", array_type)
+            // The whole shape, e.g. Int[3][4], an omitted length shows as []
+            VarType::Array((basic_type, dims)) => {
+                write!(f, "Array: {:?}", basic_type)?;
+                for dim in dims {
+                    match dim {
+                        0 => write!(f, "[]")?,
+                        dim => write!(f, "[{}]", dim)?,
+                    }
+                }
+                Ok(())
             }
         }
     }
 }
 
+// Plain `int` keeps its short name, other widths show their type like Int(u8)
+impl std::fmt::Debug for BasicType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result {
+        match self {
+            BasicType::Int(t) if *t == IntType::INT => write!(f, "Int"),
+            BasicType::Int(t) => write!(f, "Int({})", t),
+            BasicType::Char => write!(f, "Char"),
+            BasicType::Float => write!(f, "Float"),
+            BasicType::Double => write!(f, "Double"),
+            BasicType::Bool => write!(f, "Bool"),
+            BasicType::String => write!(f, "String"),
+            BasicType::Struct(name) => write!(f, "Struct({:?})", name),
+            BasicType::Pointer(t) => write!(f, "Pointer({:?})", t),
+            BasicType::Null => write!(f, "Null"),
+            BasicType::Void => write!(f, "Void"),
+            BasicType::Error => write!(f, "Error"),
+        }
+    }
+}
+
 impl Display for BasicType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result {
         write!(f, "{:?}", self)
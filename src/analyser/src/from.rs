@@ -1,17 +1,43 @@
-use spl_ast::tree::Value;
+use spl_ast::tree::{IntType, Value};
 use crate::symbol::*;
 
 impl From<Value> for BasicType {
     fn from(value: Value) -> BasicType {
         match value {
-            Value::Integer(_) => BasicType::Int,
+            Value::Integer(_) => BasicType::Int(IntType::INT),
+            Value::SizedInteger(_, t) => BasicType::Int(t),
             Value::Float(_) => BasicType::Float,
+            Value::Double(_) => BasicType::Double,
             Value::Char(_) => BasicType::Char,
             Value::Bool(_) => BasicType::Bool,
             Value::String(_) => BasicType::String,
             Value::Struct(obj) => BasicType::Struct(obj),
+            // `void*` points to anything like `null` does
+            Value::Pointer(e) if *e == Value::Null => BasicType::Pointer(Box::new(BasicType::Null)),
             Value::Pointer(e) => BasicType::Pointer(Box::new(BasicType::from(*e))),
-            Value::Null => BasicType::Null
+            // Only left in declarations whose type parameters were already reported
+            Value::TypeParam(_) => BasicType::Null,
+            // Replaced by the walker, which infers it from the initializer
+            Value::Auto => BasicType::Error,
+            Value::Null => BasicType::Void
+        }
+    }
+}
+
+// Argument types from which generic functions are instantiated
+impl From<BasicType> for Value {
+    fn from(value: BasicType) -> Value {
+        match value {
+            BasicType::Int(t) if t == IntType::INT => Value::Integer(0),
+            BasicType::Int(t) => Value::SizedInteger(0, t),
+            BasicType::Char => Value::Char(' '),
+            BasicType::Float => Value::Float(0.0),
+            BasicType::Double => Value::Double(0.0),
+            BasicType::Bool => Value::Bool(false),
+            BasicType::String => Value::String(String::new()),
+            BasicType::Struct(obj) => Value::Struct(obj),
+            BasicType::Pointer(e) => Value::Pointer(Box::new(Value::from(*e))),
+            BasicType::Null | BasicType::Void | BasicType::Error => Value::Null
         }
     }
 }
\ No newline at end of file
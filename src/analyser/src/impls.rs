@@ -1,5 +1,15 @@
 use crate::symbol::*;
 
+impl VarType {
+    pub fn error() -> VarType {
+        VarType::Primitive(BasicType::Error)
+    }
+
+    pub fn is_error(&self) -> bool {
+        *self == VarType::Primitive(BasicType::Error)
+    }
+}
+
 impl<T> Symbol<T> {
     pub fn new(id: i32, is_global: bool, identifier: String, symbol_type: T) -> Symbol<T>{
         Symbol {
@@ -15,8 +25,9 @@ impl PartialEq for VarType {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (VarType::Primitive(a), VarType::Primitive(b)) => a == b,
+            // Arrays have the same shape, an omitted length only equals another
             (VarType::Array((type_a, dims_a)), VarType::Array((type_b, dims_b))) => {
-                type_a == type_b && dims_a.len() == dims_b.len()
+                type_a == type_b && dims_a == dims_b
             }
             _ => false,
         }
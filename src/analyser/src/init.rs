@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+use spl_lexer::tokens::Span;
+
+/*
+    Definite assignment of the local variables of a function. The walker
+    keeps the set of variables assigned on every path to the statement it
+    is at: branches are walked from the same set and joined by intersection,
+    a loop body may not run at all, and nothing is reachable after a return,
+    break or continue until the paths join again.
+*/
+
+// Variables assigned on every path, None where no path reaches
+type Assigned = Option<HashSet<i32>>;
+
+fn join(a: Assigned, b: Assigned) -> Assigned {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.intersection(&b).copied().collect()),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+#[derive(Default)]
+pub struct InitTracker {
+    // Variables declared without a value, and the statements declaring them
+    declared: HashMap<i32, Span>,
+    assigned: Assigned,
+    // Enclosing loops, innermost last: the label and the paths that break out of it
+    loops: Vec<(Option<String>, Assigned)>,
+}
+
+impl InitTracker {
+    pub fn enter_function(&mut self) {
+        self.assigned = Some(HashSet::new());
+        self.loops.clear();
+    }
+
+    // A declaration inside a loop starts over in each iteration
+    pub fn declare(&mut self, id: i32, span: Span) {
+        self.declared.insert(id, span);
+        if let Some(assigned) = self.assigned.as_mut() {
+            assigned.remove(&id);
+        }
+    }
+
+    pub fn assign(&mut self, id: i32) {
+        if let Some(assigned) = self.assigned.as_mut() {
+            assigned.insert(id);
+        }
+    }
+
+    // Declaration of a variable that may be unassigned here. It counts
+    // as assigned afterwards, so each path reports it once.
+    pub fn check(&mut self, id: i32) -> Option<&Span> {
+        let span = self.declared.get(&id)?;
+        let assigned = self.assigned.as_mut()?;
+        if assigned.insert(id) {
+            Some(span)
+        } else {
+            None
+        }
+    }
+
+    pub fn snapshot(&self) -> Assigned {
+        self.assigned.clone()
+    }
+
+    pub fn restore(&mut self, assigned: Assigned) {
+        self.assigned = assigned;
+    }
+
+    // Paths of two branches meet after them
+    pub fn merge(&mut self, other: Assigned) {
+        self.assigned = join(self.assigned.take(), other);
+    }
+
+    // Return and continue, the following statements are not reached
+    pub fn leave(&mut self) {
+        self.assigned = None;
+    }
+
+    pub fn enter_loop(&mut self, label: &Option<String>) {
+        self.loops.push((label.clone(), None));
+    }
+
+    pub fn break_loop(&mut self, label: &Option<String>) {
+        let target = match label {
+            Some(label) => self.loops.iter_mut().rev().find(|(l, _)| l.as_ref() == Some(label)),
+            None => self.loops.last_mut(),
+        };
+        if let Some((_, breaks)) = target {
+            *breaks = join(breaks.take(), self.assigned.clone());
+        }
+        self.leave();
+    }
+
+    // After a loop come the paths that skip or end it, given by `ended`,
+    // and those that break out of it
+    pub fn exit_loop(&mut self, ended: Assigned) {
+        let (_, breaks) = self.loops.pop().unwrap_or_default();
+        self.assigned = join(ended, breaks);
+    }
+}
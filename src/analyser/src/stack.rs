@@ -2,13 +2,20 @@ use crate::table::ScopeTable;
 use crate::symbol::{VarSymbol, FuncSymbol, StructType};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use crate::error::SemanticError;
+use spl_ast::eval::ConstValue;
 
 #[derive(Clone, Debug)]
 pub struct ScopeStack {
-    pub func_scope: Rc<RefCell<ScopeTable<FuncSymbol>>>,
+    // Overloads of each function name, in the order they are defined
+    pub func_scope: Rc<RefCell<ScopeTable<Vec<FuncSymbol>>>>,
     pub struct_scope: Rc<RefCell<ScopeTable<StructType>>>,
+    // Structs that are declared but not yet defined
+    pub forward_structs: Rc<RefCell<HashSet<String>>>,
     pub stack: Vec<Rc<RefCell<ScopeTable<VarSymbol>>>>,
+    // Folded values of the const variables, one table per scope of `stack`
+    pub consts: Vec<Rc<RefCell<ScopeTable<ConstValue>>>>,
     depth: usize,
 }
 
@@ -16,19 +23,23 @@ impl ScopeStack {
     pub fn new() -> Self {
         let func_scope = Rc::new(RefCell::new(ScopeTable::new()));
         let struct_scope = Rc::new(RefCell::new(ScopeTable::new()));
+        let forward_structs = Rc::new(RefCell::new(HashSet::new()));
         let stack = vec![Rc::new(RefCell::new(ScopeTable::new()))];
-        ScopeStack { func_scope, struct_scope, stack , depth: 0}
+        let consts = vec![Rc::new(RefCell::new(ScopeTable::new()))];
+        ScopeStack { func_scope, struct_scope, forward_structs, stack, consts, depth: 0}
     }
 
     // Scope Relevant
     pub fn extend_scope(&mut self) {
         self.stack.push(Rc::new(RefCell::new(ScopeTable::new())));
+        self.consts.push(Rc::new(RefCell::new(ScopeTable::new())));
         self.depth += 1;
     }
 
     pub fn exit_scope(&mut self) -> Result<(), SemanticError> {
         if self.depth > 0 {
             self.stack.pop();
+            self.consts.pop();
             self.depth -= 1;
             Ok(())
         }else{
@@ -40,11 +51,15 @@ impl ScopeStack {
         }
     }
 
+    pub fn is_global_scope(&self) -> bool {
+        self.depth == 0
+    }
+
     pub fn get_current_scope(&self) -> ScopeTable<VarSymbol> {
         self.stack.last().unwrap().borrow().clone()
     }
 
-    pub fn get_current_func_scope(&self) -> ScopeTable<FuncSymbol>{ 
+    pub fn get_current_func_scope(&self) -> ScopeTable<Vec<FuncSymbol>>{ 
         self.func_scope.borrow().clone()
     }
 
@@ -84,6 +99,23 @@ impl ScopeStack {
         })
     }
 
+    // The variable must be defined in the current scope already
+    pub fn define_const(&self, identifier: &str, value: ConstValue) {
+        self.consts.last().unwrap().borrow_mut().insert(identifier.to_string(), value);
+    }
+
+    // Value of a const variable, None if the innermost variable
+    // with this name is not a constant
+    pub fn get_const(&self, identifier: &str) -> Option<ConstValue> {
+        let identifier = identifier.to_string();
+        for (vars, consts) in self.stack.iter().zip(self.consts.iter()).rev() {
+            if vars.borrow().lookup(&identifier).is_some() {
+                return consts.borrow().lookup(&identifier).copied();
+            }
+        }
+        None
+    }
+
     // Struct Relevant
     pub fn define_struct(&self, struct_type: StructType) -> Result<(), SemanticError> {
         let (identifier, fields) = struct_type.clone();
@@ -106,15 +138,35 @@ impl ScopeStack {
                 line: 0,
             });
         }else {
+            self.forward_structs.borrow_mut().remove(&identifier);
             self.struct_scope.borrow_mut().insert(identifier.clone(), struct_type);
             Ok(())
         }
     }
 
+    // A forward declaration can be repeated and may follow the definition
+    pub fn declare_struct(&self, identifier: &String) {
+        if self.struct_scope.borrow().lookup(identifier).is_none() {
+            self.forward_structs.borrow_mut().insert(identifier.clone());
+        }
+    }
+
+    // Declared structs can be pointed to even if they are still incomplete
+    pub fn is_struct_declared(&self, identifier: &String) -> bool {
+        self.struct_scope.borrow().lookup(identifier).is_some() || self.forward_structs.borrow().contains(identifier)
+    }
+
     pub fn get_struct(&self, type_t: &String) -> Result<StructType, SemanticError> {
         if let Some(struct_type) = self.struct_scope.borrow().lookup(type_t) {
             return Ok(struct_type.clone());
         }
+        if self.forward_structs.borrow().contains(type_t) {
+            return Err(SemanticError::TypeError {
+                id: 16,
+                message: format!("Struct {} is incomplete, it can only be used through pointers", type_t),
+                line: 0,
+            });
+        }
         Err(SemanticError::ReferenceError {
             id: 14,
             variable: type_t.clone(),
@@ -123,22 +175,26 @@ impl ScopeStack {
     }
 
     // Function Relevant
+    // Functions may be overloaded on their parameter types, but not on the
+    // return type alone, and `main` has a single definition
     pub fn define_func_symbol(&self, symbol: FuncSymbol) -> Result<(), SemanticError> {
-        if self.func_scope.borrow().lookup(&symbol.identifier).is_some() {
+        let mut func_scope = self.func_scope.borrow_mut();
+        let overloads = func_scope.symbols.entry(symbol.identifier.clone()).or_default();
+        if overloads.iter().any(|f| f.symbol_type.1 == symbol.symbol_type.1 || symbol.identifier == "main") {
             return Err(SemanticError::RedefinitionError {
                 id: 4,
                 variable: symbol.identifier.clone(),
                 line: 0,
             });
         }else {
-            self.func_scope.borrow_mut().insert(symbol.identifier.clone(), symbol);
+            overloads.push(symbol);
             Ok(())
         }
     }
 
-    pub fn get_func_symbol(&self, identifier: &String) -> Result<FuncSymbol, SemanticError> {
-        if let Some(symbol) = self.func_scope.borrow().lookup(identifier) {
-            return Ok(symbol.clone());
+    pub fn get_func_symbols(&self, identifier: &String) -> Result<Vec<FuncSymbol>, SemanticError> {
+        if let Some(symbols) = self.func_scope.borrow().lookup(identifier) {
+            return Ok(symbols.clone());
         }
         Err(SemanticError::ReferenceError {
             id: 2,
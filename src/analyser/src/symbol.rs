@@ -1,3 +1,5 @@
+use spl_ast::tree::IntType;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Symbol<T> {
 	pub id: i32, // Unique identifier
@@ -16,6 +18,7 @@ pub type FuncSymbol = Symbol<FuncType>;
 			- Int
 			- Char
 			- Float
+			- Double
 			- Bool
 			- String
 		- Array Type `(Vec<usize>, Vec<Value>)`
@@ -33,18 +36,27 @@ pub enum VarType {
 
 // Array(BasicType::Struct, Vec<usize>: len 0) is equal to BasicType::Struct 
 pub type PrimType = BasicType;
+// A length of 0 is the omitted outermost length of an array parameter
 pub type ArrayType = (BasicType, Vec<usize>);
 pub type StructType = (String, Vec<(String, VarType)>);
 pub type FuncType = (BasicType, Vec<VarType>);
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BasicType {
-	Int,
+	// Width and signedness, `int` is IntType::INT
+	Int(IntType),
 	Char,
 	Float,
+	Double,
 	Bool,
 	String,
 	Struct(String),
 	Pointer(Box<BasicType>),
-	Null
+	// Element of `void*` and `null`, which point to anything
+	Null,
+	// Result of a function that returns nothing, it has no values
+	Void,
+	// Type of an expression that failed to check, it was reported once
+	// and is accepted everywhere so that it causes no further errors
+	Error
 }
\ No newline at end of file
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use spl_ast::tree::*;
+
+/*
+    The program handed to the code generator, with what the walker worked
+    out filled in so that it is not worked out a second time:
+    - The types of `auto` declarations, inferred from their initializers,
+      by the start of the statement, the function it is in and the name of
+      the variable. They replace the `auto` so that the declarations look
+      like any other, in each instance of a generic function with its own.
+    - The function each call refers to, by the start of the call and the
+      function it is made in. Overloaded functions are renamed after their
+      parameters and the calls follow the overload picked by the walker.
+    - The instances of generic functions, which follow the part whose
+      calls made them and replace the generic functions themselves.
+*/
+
+pub type Inferred = HashMap<(usize, String, String), Value>;
+
+#[derive(Clone, Debug)]
+pub enum Callee {
+    // (name, mangled name) of a function of the program
+    Function(String, String),
+    // Mangled name of an instance of a generic function
+    Instance(String),
+}
+
+pub type Calls = HashMap<(usize, String), Callee>;
+
+// Name of an overload, e.g. `add.int.double`. Array parameters carry their
+// lengths, e.g. `sum.int_arr3_4`, where an omitted length is 0.
+pub fn mangle_overload(name: &str, params: &[Variable]) -> String {
+    let types = params.iter().filter_map(|param| match param {
+        Variable::FormalParameter(_, ty, dims) if dims.is_empty() => Some(ty.mangle()),
+        Variable::FormalParameter(_, ty, dims) => Some(format!("{}_arr{}",
+            ty.mangle(),
+            dims.iter().map(usize::to_string).collect::<Vec<String>>().join("_")
+        )),
+        _ => None,
+    }).collect::<Vec<String>>();
+    match types.is_empty() {
+        true => format!("{}.void", name),
+        false => format!("{}.{}", name, types.join(".")),
+    }
+}
+
+// Functions sharing their name with another one, a builtin included, are
+// the overloaded ones. Instances are given by the index of their part.
+pub fn resolve_program(program: &Program, inferred: &Inferred, calls: &Calls, overloaded: HashSet<String>, instances: &[(usize, Function)]) -> Program {
+    let Program::Program(parts) = program else {
+        return Program::Error;
+    };
+    let mut resolver = Resolver {
+        inferred,
+        calls,
+        overloaded,
+        caller: String::new(),
+    };
+    // Instances take the span of their generic function
+    let mut generics = HashMap::new();
+    let mut resolved = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            ProgramPart::Function(function, span) => match function.as_ref() {
+                Function::Generic(_, decl) => {
+                    if let Function::FuncDeclaration(name, ..) = decl.as_ref() {
+                        generics.insert(name.to_string(), span.clone());
+                    }
+                }
+                _ => {
+                    let mut function = function.clone();
+                    resolver.resolve_function(&mut function);
+                    resolved.push(ProgramPart::Function(function, span.clone()));
+                }
+            },
+            ProgramPart::Statement(statement) => {
+                let mut statement = statement.clone();
+                resolver.resolve_statement(&mut statement);
+                resolved.push(ProgramPart::Statement(statement));
+            }
+        }
+        for (_, instance) in instances.iter().filter(|(part, _)| *part == i) {
+            let Function::FuncDeclaration(name, ..) = instance else {
+                continue;
+            };
+            // Mangled names start with the name of the generic function
+            let Some(span) = name.split('.').next().and_then(|generic| generics.get(generic)) else {
+                continue;
+            };
+            let mut instance = Box::new(instance.clone());
+            resolver.resolve_function(&mut instance);
+            resolved.push(ProgramPart::Function(instance, span.clone()));
+        }
+    }
+    Program::Program(resolved)
+}
+
+struct Resolver<'a> {
+    inferred: &'a Inferred,
+    calls: &'a Calls,
+    overloaded: HashSet<String>,
+    // Function the calls are made in, empty for global variables
+    caller: String,
+}
+
+impl Resolver<'_> {
+    fn resolve_statement(&mut self, statement: &mut Statement) {
+        self.caller.clear();
+        if let Statement::GlobalVariable(vars, _) = statement {
+            vars.iter_mut().for_each(|var| self.resolve_variable(var));
+        }
+    }
+
+    fn resolve_function(&mut self, function: &mut Function) {
+        match function {
+            Function::FuncDeclaration(name, params, _, body) => {
+                self.caller = name.to_string();
+                if self.overloaded.contains(name.as_str()) {
+                    **name = mangle_overload(name, params);
+                }
+                self.resolve_body(body);
+            }
+            Function::FuncReference(name, args, span) => {
+                args.iter_mut().for_each(|arg| self.resolve_comp_expr(arg));
+                match self.calls.get(&(span.start, self.caller.clone())) {
+                    Some(Callee::Function(function, mangled)) => **name = match self.overloaded.contains(function) {
+                        true => mangled.clone(),
+                        false => function.clone(),
+                    },
+                    Some(Callee::Instance(instance)) => **name = instance.clone(),
+                    None => {}
+                }
+            }
+            Function::Generic(..) | Function::Error => {}
+        }
+    }
+
+    fn resolve_body(&mut self, body: &mut Body) {
+        if let Body::Body(exprs) = body {
+            exprs.iter_mut().for_each(|expr| self.resolve_expr(expr));
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::VarManagement(vars, span) => {
+                for var in vars.iter_mut() {
+                    if let Variable::VarDeclaration(name, ty, dims) = var {
+                        if **ty == Value::Auto {
+                            match self.inferred.get(&(span.start, self.caller.clone(), name.to_string())) {
+                                Some(Value::Struct(obj)) => *var = Variable::StructDeclaration(Box::new(obj.clone()), name.clone(), dims.clone()),
+                                Some(ty) => *var = Variable::VarDeclaration(name.clone(), Box::new(ty.clone()), dims.clone()),
+                                None => {}
+                            }
+                        }
+                    }
+                    self.resolve_variable(var);
+                }
+            }
+            Expr::If(If::IfExpr(cond, body), _) => {
+                self.resolve_cond_expr(cond);
+                self.resolve_body(body);
+            }
+            Expr::If(If::IfElseExpr(cond, then, otherwise), _) => {
+                self.resolve_cond_expr(cond);
+                self.resolve_body(then);
+                self.resolve_body(otherwise);
+            }
+            Expr::Loop(Loop::WhileExpr(cond, body), ..) => {
+                self.resolve_cond_expr(cond);
+                self.resolve_body(body);
+            }
+            Expr::Loop(Loop::ForExpr(init, cond, step, body), ..) => {
+                self.resolve_expr(init);
+                self.resolve_cond_expr(cond);
+                self.resolve_expr(step);
+                self.resolve_body(body);
+            }
+            Expr::FuncCall(function, _) => self.resolve_function(function),
+            Expr::Body(body, _) => self.resolve_body(body),
+            Expr::Return(expr, _) | Expr::Delete(expr, _) => self.resolve_comp_expr(expr),
+            _ => {}
+        }
+    }
+
+    fn resolve_variable(&mut self, var: &mut Variable) {
+        match var {
+            Variable::VarAssignment(var, values) => {
+                self.resolve_variable(var);
+                values.iter_mut().for_each(|value| self.resolve_comp_expr(value));
+            }
+            Variable::VarReference(_, offsets)
+            | Variable::VarDeclaration(_, _, offsets)
+            | Variable::StructDeclaration(_, _, offsets) => {
+                offsets.iter_mut().for_each(|offset| self.resolve_comp_expr(offset));
+            }
+            Variable::ConstDeclaration(_, _, value) => self.resolve_comp_expr(value),
+            Variable::StructReference(vars) => vars.iter_mut().for_each(|var| self.resolve_variable(var)),
+            Variable::Dereference(expr) => self.resolve_comp_expr(expr),
+            _ => {}
+        }
+    }
+
+    fn resolve_comp_expr(&mut self, expr: &mut CompExpr) {
+        match expr {
+            CompExpr::Variable(var) => self.resolve_variable(var),
+            CompExpr::FuncCall(function) => self.resolve_function(function),
+            CompExpr::UnaryOperation(_, expr) | CompExpr::Designated(_, expr) | CompExpr::New(_, Some(expr)) => {
+                self.resolve_comp_expr(expr);
+            }
+            CompExpr::BinaryOperation(lhs, _, rhs) => {
+                self.resolve_comp_expr(lhs);
+                self.resolve_comp_expr(rhs);
+            }
+            CompExpr::InitList(items) => items.iter_mut().for_each(|item| self.resolve_comp_expr(item)),
+            _ => {}
+        }
+    }
+
+    fn resolve_cond_expr(&mut self, cond: &mut CondExpr) {
+        match cond {
+            CondExpr::UnaryCondition(_, cond) => self.resolve_cond_expr(cond),
+            CondExpr::BinaryCondition(lhs, _, rhs) => {
+                self.resolve_cond_expr(lhs);
+                self.resolve_cond_expr(rhs);
+            }
+            CondExpr::Condition(lhs, _, rhs) => {
+                self.resolve_comp_expr(lhs);
+                self.resolve_comp_expr(rhs);
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,11 +1,24 @@
-use crate::symbol::{BasicType, VarType};
+use crate::symbol::{BasicType, FuncSymbol, StructType, VarType};
 use crate::error::SemanticError;
+use crate::table::ScopeTable;
+use spl_ast::tree::{BinaryOperator, Body, Expr, If, IntType, JudgeOperator, Loop, Value, Variable};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 
 pub struct TypeChecker{
     pub current_scope: ScopeType,
     pub current_type: BasicType,
-    pub func_ret_type: BasicType
+    pub func_ret_type: BasicType,
+    // Enclosing loops of the current position, innermost last.
+    // Each entry is the optional label of the loop.
+    pub loops: Vec<Option<String>>,
+    // Every label of the current function, used to tell a label
+    // of a loop that does not enclose the jump from an unknown one.
+    pub labels: Vec<String>,
+    // The struct definitions when structs are equivalent by their fields,
+    // otherwise only structs of the same name are
+    pub structs: Option<Rc<RefCell<ScopeTable<StructType>>>>
 }
 
 impl TypeChecker {
@@ -13,11 +26,31 @@ impl TypeChecker {
         TypeChecker{
             current_scope: ScopeType::Global,
             current_type: BasicType::Null,
-            func_ret_type: BasicType::Null
+            func_ret_type: BasicType::Null,
+            loops: Vec::new(),
+            labels: Vec::new(),
+            structs: None
         }
     }
 
     pub fn check_binary_operations(&self, ltype: VarType, rtype: VarType) -> Result<VarType, SemanticError> {
+        // Characters are promoted to int before taking part in arithmetic
+        let promote = |t: VarType| match t {
+            VarType::Primitive(BasicType::Char) => VarType::Primitive(BasicType::Int(IntType::INT)),
+            t => t,
+        };
+        let (ltype, rtype) = (promote(ltype), promote(rtype));
+        if ltype.is_error() || rtype.is_error() {
+            return Ok(VarType::error());
+        }
+        // Integers of different widths meet at their common type
+        if let (VarType::Primitive(BasicType::Int(l)), VarType::Primitive(BasicType::Int(r))) = (&ltype, &rtype) {
+            return Ok(VarType::Primitive(BasicType::Int(l.common(*r))));
+        }
+        // A float meeting a double is promoted to double
+        if let (VarType::Primitive(BasicType::Float | BasicType::Double), VarType::Primitive(BasicType::Float | BasicType::Double)) = (&ltype, &rtype) {
+            return Ok(if ltype == rtype { ltype } else { VarType::Primitive(BasicType::Double) });
+        }
         if ltype == rtype {
             return Ok(ltype);
         } else {
@@ -29,8 +62,153 @@ impl TypeChecker {
         }
     }
 
+    // Strings can only be concatenated with another string.
+    pub fn check_string_operation(&self, op: &BinaryOperator, ltype: VarType, rtype: VarType) -> Result<VarType, SemanticError> {
+        match (op, &ltype, &rtype) {
+            (BinaryOperator::Add, VarType::Primitive(BasicType::String), VarType::Primitive(BasicType::String)) => Ok(ltype),
+            _ => Err(SemanticError::ImproperUsageError {
+                id: 7,
+                message: format!("Strings only support concatenation with +, conducting {} between {} and {}", op, ltype, rtype),
+                line: 0,
+            })
+        }
+    }
+
+    // Pointer arithmetic: pointer +/- int, int + pointer and the
+    // difference of two pointers of the same type.
+    pub fn check_pointer_operation(&self, op: &BinaryOperator, ltype: VarType, rtype: VarType) -> Result<VarType, SemanticError> {
+        match (op, &ltype, &rtype) {
+            (BinaryOperator::Add | BinaryOperator::Sub,
+                VarType::Primitive(BasicType::Pointer(_)), VarType::Primitive(BasicType::Int(_))) => Ok(ltype),
+            (BinaryOperator::Add,
+                VarType::Primitive(BasicType::Int(_)), VarType::Primitive(BasicType::Pointer(_))) => Ok(rtype),
+            (BinaryOperator::Sub,
+                VarType::Primitive(BasicType::Pointer(_)), VarType::Primitive(BasicType::Pointer(_))) if self.is_same_var(&ltype, &rtype) => {
+                Ok(VarType::Primitive(BasicType::Int(IntType::INT)))
+            }
+            _ => Err(SemanticError::ImproperUsageError {
+                id: 7,
+                message: format!("Invalid pointer arithmetic, conducting {} between {} and {}", op, ltype, rtype),
+                line: 0,
+            })
+        }
+    }
+
+    // `new T[count]` yields a T*, the element type cannot be void
+    // and the count has to be an integer.
+    pub fn check_allocation(&self, ty: BasicType, count: Option<VarType>) -> Result<VarType, SemanticError> {
+        if ty == BasicType::Void {
+            return Err(SemanticError::ImproperUsageError {
+                id: 19,
+                message: "Cannot allocate values of type void".to_owned(),
+                line: 0,
+            });
+        }
+        match count {
+            None | Some(VarType::Primitive(BasicType::Int(_) | BasicType::Error)) => Ok(VarType::Primitive(BasicType::Pointer(Box::new(ty)))),
+            Some(t) => Err(SemanticError::ImproperUsageError {
+                id: 19,
+                message: format!("Allocation count should be of type Int, found {}", t),
+                line: 0,
+            })
+        }
+    }
+
+    // Only memory behind a typed pointer can be released.
+    pub fn check_delete(&self, ty: VarType) -> Result<(), SemanticError> {
+        match ty {
+            VarType::Primitive(BasicType::Pointer(t)) if *t != BasicType::Null => Ok(()),
+            VarType::Primitive(BasicType::Error) => Ok(()),
+            _ => Err(SemanticError::ImproperUsageError {
+                id: 19,
+                message: format!("Only pointers can be deleted, found {}", ty),
+                line: 0,
+            })
+        }
+    }
+
+    // Same types are compatible, besides, `null` (and void*) can be
+    // converted to and from any pointer type, integers and characters convert
+    // implicitly between widths and so do float and double.
+    pub fn is_compatible(&self, ltype: &VarType, rtype: &VarType) -> bool {
+        match (ltype, rtype) {
+            (VarType::Primitive(BasicType::Error), _) | (_, VarType::Primitive(BasicType::Error)) => true,
+            (VarType::Primitive(BasicType::Int(_) | BasicType::Char), VarType::Primitive(BasicType::Int(_) | BasicType::Char)) => true,
+            (VarType::Primitive(BasicType::Float | BasicType::Double), VarType::Primitive(BasicType::Float | BasicType::Double)) => true,
+            (VarType::Primitive(BasicType::Pointer(l)), VarType::Primitive(BasicType::Pointer(r))) => {
+                self.is_same(l, r) || **l == BasicType::Null || **r == BasicType::Null
+            }
+            _ => self.is_same_var(ltype, rtype)
+        }
+    }
+
+    // Types are the same when they are equal, except that in structural
+    // mode structs with the same field types in the same order are too
+    pub fn is_same(&self, ltype: &BasicType, rtype: &BasicType) -> bool {
+        self.same_type(ltype, rtype, &mut Vec::new())
+    }
+
+    pub fn is_same_var(&self, ltype: &VarType, rtype: &VarType) -> bool {
+        self.same_var_type(ltype, rtype, &mut Vec::new())
+    }
+
+    // `assumed` holds the pairs of structs being compared further up, they
+    // are taken to be the same so that self-referencing structs terminate
+    fn same_type(&self, ltype: &BasicType, rtype: &BasicType, assumed: &mut Vec<(String, String)>) -> bool {
+        match (ltype, rtype) {
+            (BasicType::Struct(l), BasicType::Struct(r)) => self.same_struct(l, r, assumed),
+            (BasicType::Pointer(l), BasicType::Pointer(r)) => self.same_type(l, r, assumed),
+            _ => ltype == rtype
+        }
+    }
+
+    fn same_var_type(&self, ltype: &VarType, rtype: &VarType, assumed: &mut Vec<(String, String)>) -> bool {
+        match (ltype, rtype) {
+            (VarType::Primitive(l), VarType::Primitive(r)) => self.same_type(l, r, assumed),
+            (VarType::Array((l, ldims)), VarType::Array((r, rdims))) => ldims == rdims && self.same_type(l, r, assumed),
+            _ => false
+        }
+    }
+
+    fn same_struct(&self, l: &String, r: &String, assumed: &mut Vec<(String, String)>) -> bool {
+        if l == r {
+            return true;
+        }
+        let Some(structs) = &self.structs else {
+            return false;
+        };
+        if assumed.contains(&(l.clone(), r.clone())) {
+            return true;
+        }
+        // Incomplete structs have no fields to compare
+        let (lfields, rfields) = match (structs.borrow().lookup(l), structs.borrow().lookup(r)) {
+            (Some((_, lfields)), Some((_, rfields))) => (lfields.clone(), rfields.clone()),
+            _ => return false,
+        };
+        assumed.push((l.clone(), r.clone()));
+        lfields.len() == rfields.len() && lfields.iter().zip(&rfields).all(|((_, lt), (_, rt))| {
+            self.same_var_type(lt, rt, assumed)
+        })
+    }
+
+    // Array arguments must have the shape of the parameter. A parameter
+    // that omits its outermost length, like `int a[][3]`, takes arrays of
+    // any length and, if it is 1-D, pointers as well.
+    pub fn is_param_compatible(&self, param: &VarType, arg: &VarType) -> bool {
+        match (param, arg) {
+            (VarType::Array((pt, pdims)), VarType::Array((at, adims))) => {
+                self.is_same(pt, at) && pdims.len() == adims.len()
+                    && (pdims[0] == 0 || pdims[0] == adims[0]) && pdims[1..] == adims[1..]
+            }
+            (VarType::Array((pt, pdims)), VarType::Primitive(BasicType::Pointer(at))) => {
+                *pdims == [0] && self.is_same(pt, at)
+            }
+            _ => self.is_compatible(param, arg)
+        }
+    }
+
     pub fn check_assign_operation(&self, ltype: VarType, rtype: VarType) -> Result<VarType, SemanticError> {
-        if ltype == rtype {
+        if self.is_compatible(&ltype, &rtype) {
             return Ok(rtype);
         } else {
             return Err(SemanticError::ImproperUsageError {
@@ -41,18 +219,33 @@ impl TypeChecker {
         }
     }
 
-    pub fn check_condition(&self, ltype: VarType, rtype: VarType) -> Result<BasicType, SemanticError>{
+    pub fn check_condition(&self, op: &JudgeOperator, ltype: VarType, rtype: VarType) -> Result<BasicType, SemanticError>{
         match (ltype, rtype) {
-            (VarType::Primitive(BasicType::Int), VarType::Primitive(BasicType::Int)) => {
+            (VarType::Primitive(BasicType::Error), _) | (_, VarType::Primitive(BasicType::Error)) => {
+                Ok(BasicType::Bool)
+            }
+            // Structs are compared member by member, they have no ordering
+            (VarType::Primitive(l @ BasicType::Struct(_)), VarType::Primitive(r @ BasicType::Struct(_)))
+                if self.is_same(&l, &r) && matches!(op, JudgeOperator::EQ | JudgeOperator::NE) => {
+                Ok(BasicType::Bool)
+            }
+            (VarType::Primitive(BasicType::Int(_) | BasicType::Char), VarType::Primitive(BasicType::Int(_) | BasicType::Char)) => {
+                Ok(BasicType::Bool)
+            }
+            (VarType::Primitive(BasicType::Float | BasicType::Double), VarType::Primitive(BasicType::Float | BasicType::Double)) => {
                 Ok(BasicType::Bool)
             }
-            (VarType::Primitive(BasicType::Float), VarType::Primitive(BasicType::Float)) => {
+            (VarType::Primitive(BasicType::String), VarType::Primitive(BasicType::String)) => {
+                Ok(BasicType::Bool)
+            }
+            (l @ VarType::Primitive(BasicType::Pointer(_)), r @ VarType::Primitive(BasicType::Pointer(_)))
+                if self.is_compatible(&l, &r) => {
                 Ok(BasicType::Bool)
             }
             _ => {
                 Err(SemanticError::TypeError{ 
                     id: 7, 
-                    message: "Only type Int and type Float are supported in condition.".to_owned(), 
+                    message: "Only type Int, type Float and pointers are supported in condition, structs only support == and !=.".to_owned(), 
                     line: 0
                 })
             }
@@ -61,7 +254,7 @@ impl TypeChecker {
 
     pub fn check_binary_condition(&self, ltype: BasicType, rtype: BasicType) -> Result<BasicType, SemanticError>{
         match (ltype, rtype) {
-            (BasicType::Bool, BasicType::Bool) => {
+            (BasicType::Bool | BasicType::Error, BasicType::Bool | BasicType::Error) => {
                 Ok(BasicType::Bool)
             }
             _ => {
@@ -74,8 +267,21 @@ impl TypeChecker {
         }
     }
 
+    // Void functions return with a bare `return;`, which is checked as
+    // returning the void type, the others return a value
     pub fn check_ret_type(&self, type_t: BasicType) -> Result<(), SemanticError>{
-        if type_t == self.func_ret_type {
+        let void_error = |message: String| Err(SemanticError::ImproperUsageError {
+            id: 29,
+            message,
+            line: 0
+        });
+        match (&self.func_ret_type, &type_t) {
+            (_, BasicType::Error) | (BasicType::Void, BasicType::Void) => return Ok(()),
+            (BasicType::Void, _) => return void_error(format!("A function returning Void cannot return a value, found {}", type_t)),
+            (_, BasicType::Void) => return void_error(format!("A return without a value in a function returning {}", self.func_ret_type)),
+            _ => {}
+        }
+        if self.is_compatible(&VarType::Primitive(self.func_ret_type.clone()), &VarType::Primitive(type_t.clone())) {
             Ok(())
         } else {
             Err(SemanticError::TypeError{
@@ -95,7 +301,7 @@ impl TypeChecker {
             });
         }
         for i in 0..params.len() {
-            if params[i] != args[i] {
+            if !self.is_param_compatible(&params[i], &args[i]) {
                 return Err(SemanticError::TypeError{
                     id: 10,
                     message: format!("The type of the {}th argument does not match the type of the parameter. Expected {}, but got {}", i+1, params[i], args[i]),
@@ -106,6 +312,126 @@ impl TypeChecker {
         Ok(())
     }
 
+    // Pick the overload a call refers to, the one that takes every argument
+    // at least as well as the others: exact matches first, then promotions
+    // which keep every value, then any other implicit conversion.
+    // A function without overloads reports its mismatching argument.
+    pub fn resolve_overload(&self, name: &str, overloads: &[FuncSymbol], args: Vec<VarType>) -> Result<FuncSymbol, SemanticError>{
+        if let [func] = overloads {
+            return self.check_func_params(func.symbol_type.1.clone(), args).map(|()| func.clone());
+        }
+        let viable: Vec<(&FuncSymbol, Vec<Fit>)> = overloads.iter().filter_map(|func| {
+            let params = &func.symbol_type.1;
+            if params.len() != args.len() {
+                return None;
+            }
+            let fits = params.iter().zip(&args).map(|(param, arg)| self.fit(param, arg)).collect::<Option<Vec<Fit>>>()?;
+            Some((func, fits))
+        }).collect();
+        // Failed arguments fit any overload, the result is only known
+        // if the overloads agree on it
+        if let Some((first, _)) = viable.first().filter(|_| args.iter().any(VarType::is_error)) {
+            let mut func = (*first).clone();
+            if viable.iter().any(|(f, _)| f.symbol_type.0 != func.symbol_type.0) {
+                func.symbol_type.0 = BasicType::Error;
+            }
+            return Ok(func);
+        }
+        // Whether an overload takes every argument at least as well as another
+        let covers = |(f, ffits): &(&FuncSymbol, Vec<Fit>), (g, gfits): &(&FuncSymbol, Vec<Fit>)| {
+            f.symbol_type.1.iter().zip(ffits).zip(g.symbol_type.1.iter().zip(gfits))
+                .all(|((p, pfit), (q, qfit))| self.fits_better(p, *pfit, q, *qfit))
+        };
+        let best: Vec<&(&FuncSymbol, Vec<Fit>)> = viable.iter()
+            .filter(|c| viable.iter().all(|d| std::ptr::eq(*c, d) || covers(c, d)))
+            .collect();
+        if let [(func, _)] = best.as_slice() {
+            return Ok((*func).clone());
+        }
+        // The ambiguity is between overloads no other one does better than
+        let unbeaten: Vec<&FuncSymbol> = viable.iter()
+            .filter(|c| !viable.iter().any(|d| !std::ptr::eq(*c, d) && covers(d, c) && !covers(c, d)))
+            .map(|(func, _)| *func)
+            .collect();
+        match unbeaten.as_slice() {
+            [first, second, ..] => Err(SemanticError::ImproperUsageError{
+                id: 25,
+                message: format!("The call of {} with the arguments {} is ambiguous between {} and {}",
+                    name, signature(&args), signature(&first.symbol_type.1), signature(&second.symbol_type.1)),
+                line: 0
+            }),
+            _ => Err(SemanticError::TypeError{
+                id: 10,
+                message: format!("No overload of {} takes the arguments {}", name, signature(&args)),
+                line: 0
+            }),
+        }
+    }
+
+    fn fit(&self, param: &VarType, arg: &VarType) -> Option<Fit> {
+        if self.is_same_var(param, arg) {
+            Some(Fit::Exact)
+        } else if self.is_promotion(param, arg) {
+            Some(Fit::Promotion)
+        } else if self.is_param_compatible(param, arg) {
+            Some(Fit::Conversion)
+        } else {
+            None
+        }
+    }
+
+    // Promotions widen an integer or a character to an integer that holds
+    // all of its values and a float to a double. Arrays passed to a
+    // parameter that omits its length are promoted as well.
+    fn is_promotion(&self, param: &VarType, arg: &VarType) -> bool {
+        match (param, arg) {
+            (VarType::Primitive(BasicType::Int(p)), VarType::Primitive(BasicType::Int(a))) => {
+                p.bits > a.bits && (p.signed || !a.signed)
+            }
+            (VarType::Primitive(BasicType::Int(p)), VarType::Primitive(BasicType::Char)) => p.bits > 8 && p.signed,
+            (VarType::Primitive(BasicType::Double), VarType::Primitive(BasicType::Float)) => true,
+            (VarType::Array(_), _) => self.is_param_compatible(param, arg),
+            _ => false
+        }
+    }
+
+    // Whether parameter p takes its argument at least as well as parameter q,
+    // of two promotions the narrower parameter is the closer one
+    fn fits_better(&self, p: &VarType, pfit: Fit, q: &VarType, qfit: Fit) -> bool {
+        match (pfit, qfit) {
+            (Fit::Promotion, Fit::Promotion) => self.is_same_var(p, q) || self.is_promotion(q, p),
+            _ => pfit <= qfit
+        }
+    }
+
+    // Type parameters of a generic function must be distinct, and each one
+    // must appear in a parameter so that calls can infer it.
+    pub fn check_type_params(&self, name: &str, types: &[String], inputs: &[Variable], output: &Value) -> Result<(), SemanticError>{
+        let error = |message: String| SemanticError::ImproperUsageError{
+            id: 24,
+            message,
+            line: 0
+        };
+        let params: Vec<&Value> = inputs.iter().filter_map(|input| match input {
+            Variable::FormalParameter(_, ty, _) => Some(ty.as_ref()),
+            _ => None
+        }).collect();
+        for (i, t) in types.iter().enumerate() {
+            if types[..i].contains(t) {
+                return Err(error(format!("Type parameter {} of {} is declared twice", t, name)));
+            }
+            if !params.iter().any(|ty| ty.type_param() == Some(t)) {
+                return Err(error(format!("Type parameter {} of {} does not appear in its parameters", t, name)));
+            }
+        }
+        for ty in params.iter().copied().chain(std::iter::once(output)) {
+            if let Some(t) = ty.type_param().filter(|t| !types.contains(t)) {
+                return Err(error(format!("Unknown type {} in the declaration of {}", t, name)));
+            }
+        }
+        Ok(())
+    }
+
     // This is used to check the struct field.
     // When accessing a struct field, it is ensured that the field is defined in the struct.
     pub fn check_struct_field(&self, field_name: &String, fields: &Vec<(String, VarType)>) -> Result<VarType, SemanticError>{
@@ -134,22 +460,28 @@ impl TypeChecker {
                         message: format!("Expected to have {} indices, but got {}", num_dims, num_indices),
                         line: 0
                     });
-                } else if num_indices == num_dims {
-                    for i in 0..num_indices {
-                        if reference[i] >= dims[i] {
-                            return Err(SemanticError::ImproperUsageError{
-                                id: 21,
-                                message: format!("Index {} is out of bounds: {} > {}", i, reference[i], dims[i]),
-                                line: 0
-                            });
-                        }
+                }
+                // Partially indexed arrays are checked as well, e.g. a[5] of int a[3][4]
+                for i in 0..num_indices {
+                    // A length of 0 is unknown (decayed array parameter)
+                    if dims[i] != 0 && reference[i] >= dims[i] {
+                        return Err(SemanticError::ImproperUsageError{
+                            id: 21,
+                            message: format!("Index {} is out of bounds: {} > {}", i, reference[i], dims[i]),
+                            line: 0
+                        });
                     }
+                }
+                if num_indices == num_dims {
                     return Ok(VarType::Primitive(basic_type));
                 } else {
                     let remaining_dims = dims[num_indices..].to_vec();
                     return Ok(VarType::Array((basic_type, remaining_dims)));
                 }
             },
+            VarType::Primitive(BasicType::Error) => Ok(var_type),
+            // Indexing a string reads one of its characters
+            VarType::Primitive(BasicType::String) if reference.len() == 1 => Ok(VarType::Primitive(BasicType::Char)),
             VarType::Primitive(basic_type) => {
                 if reference.len() > 0 {
                     return Err(SemanticError::ImproperUsageError{
@@ -164,6 +496,76 @@ impl TypeChecker {
     }
     
 
+    // The labels of the loops of a function body, including those after
+    // a jump, are known before it is checked
+    pub fn enter_function(&mut self, body: &Body) {
+        fn collect(body: &Body, labels: &mut Vec<String>) {
+            let Body::Body(exprs) = body else {
+                return;
+            };
+            for expr in exprs {
+                match expr {
+                    Expr::Loop(Loop::WhileExpr(_, body) | Loop::ForExpr(_, _, _, body), label, _) => {
+                        labels.extend(label.clone());
+                        collect(body, labels);
+                    }
+                    Expr::If(If::IfExpr(_, body), _) | Expr::Body(body, _) => collect(body, labels),
+                    Expr::If(If::IfElseExpr(_, then, otherwise), _) => {
+                        collect(then, labels);
+                        collect(otherwise, labels);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        collect(body, &mut self.labels);
+    }
+
+    pub fn enter_loop(&mut self, label: Option<String>) -> Result<(), SemanticError> {
+        self.loops.push(label.clone());
+        if let Some(label) = label {
+            let redefined = self.loops[..self.loops.len() - 1].iter()
+                .any(|l| l.as_ref() == Some(&label));
+            if redefined {
+                return Err(SemanticError::RedefinitionError {
+                    id: 18,
+                    variable: label,
+                    line: 0,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn exit_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    // This is used to check the target of break and continue.
+    // Without a label the innermost loop is targeted, otherwise the label
+    // must belong to one of the enclosing loops.
+    pub fn check_jump(&self, label: &Option<String>) -> Result<(), SemanticError> {
+        match label {
+            Some(label) if self.loops.iter().any(|l| l.as_ref() == Some(label)) => Ok(()),
+            Some(label) if self.labels.contains(label) => Err(SemanticError::ImproperUsageError {
+                id: 17,
+                message: format!("Loop {} does not enclose this statement", label),
+                line: 0
+            }),
+            _ if self.loops.is_empty() => Err(SemanticError::ImproperUsageError {
+                id: 17,
+                message: "Continue and break should only appear in while loop or for loop".to_owned(),
+                line: 0
+            }),
+            None => Ok(()),
+            Some(label) => Err(SemanticError::ImproperUsageError {
+                id: 17,
+                message: format!("Label {} is not defined", label),
+                line: 0
+            }),
+        }
+    }
+
     pub fn set_scope(&mut self, scope: ScopeType) -> ScopeType {
         let prev_scope = self.current_scope.clone();
         self.current_scope = scope;
@@ -184,12 +586,30 @@ impl TypeChecker {
 
     pub fn reset_ret_type(&mut self){
         self.func_ret_type = BasicType::Null;
+        self.loops.clear();
+        self.labels.clear();
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum ScopeType {
     Global,
-    Func,
-    LoopExpr
-}
\ No newline at end of file
+    Func
+}
+
+// How an argument fits its parameter, from the best to the worst
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Fit {
+    Exact,
+    Promotion,
+    Conversion
+}
+
+// Parameter or argument types of a call, e.g. (Int, Double[3])
+pub fn signature(types: &[VarType]) -> String {
+    let types: Vec<String> = types.iter().map(|t| match t {
+        VarType::Primitive(b) => format!("{}", b),
+        VarType::Array((b, dims)) => format!("{}{}", b, dims.iter().map(|d| format!("[{}]", d)).collect::<String>()),
+    }).collect();
+    format!("({})", types.join(", "))
+}
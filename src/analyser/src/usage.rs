@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+/*
+    Uses of the variables, parameters and functions of a program, counted by
+    symbol id while the walker goes through it. A symbol is reported when it
+    goes out of scope without being read, and so is an assignment whose value
+    is overwritten or dropped before any read.
+
+    Assignments are only followed along straight-line code: a block may run
+    on some paths only, or run again as a loop, so the pending assignments
+    are dropped when a block starts or ends instead of reporting them.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Local,
+    Global,
+    Parameter,
+    Function,
+}
+
+struct Usage {
+    name: String,
+    kind: Kind,
+    line: usize,
+    reads: usize,
+    // Line of the last assignment that has not been read yet
+    unread: Option<usize>,
+    // The address was taken, the value may be read through a pointer
+    escaped: bool,
+}
+
+// What is wrong with a symbol that goes out of scope
+pub enum Unused {
+    Never { name: String, kind: Kind, line: usize },
+    Assignment { name: String, line: usize },
+}
+
+#[derive(Default)]
+pub struct UsageTracker {
+    usages: HashMap<i32, Usage>,
+}
+
+impl UsageTracker {
+    pub fn declare(&mut self, id: i32, name: &str, kind: Kind, line: usize) {
+        self.usages.insert(id, Usage {
+            name: name.to_string(),
+            kind,
+            line,
+            reads: 0,
+            unread: None,
+            escaped: false,
+        });
+    }
+
+    pub fn read(&mut self, id: i32) {
+        if let Some(usage) = self.usages.get_mut(&id) {
+            usage.reads += 1;
+            usage.unread = None;
+        }
+    }
+
+    pub fn escape(&mut self, id: i32) {
+        if let Some(usage) = self.usages.get_mut(&id) {
+            usage.escaped = true;
+        }
+        self.read(id);
+    }
+
+    // Record an assignment of the whole variable, returning the line of the
+    // previous one if its value was never read. Globals may be read by any
+    // function call, so only their reads are followed.
+    pub fn write(&mut self, id: i32, line: usize) -> Option<usize> {
+        let usage = self.usages.get_mut(&id)?;
+        if usage.kind == Kind::Global || usage.escaped {
+            return None;
+        }
+        usage.unread.replace(line)
+    }
+
+    // Control flow joins here, any pending assignment may have been read
+    pub fn settle(&mut self) {
+        for usage in self.usages.values_mut() {
+            usage.unread = None;
+        }
+    }
+
+    // Forget a symbol whose scope ends, telling whether it was of any use.
+    // An assignment is only reported for symbols that are read elsewhere,
+    // the others are unused altogether.
+    pub fn release(&mut self, id: i32) -> Option<Unused> {
+        let usage = self.usages.remove(&id)?;
+        match (usage.reads, usage.unread) {
+            (0, _) => Some(Unused::Never { name: usage.name, kind: usage.kind, line: usage.line }),
+            (_, Some(line)) => Some(Unused::Assignment { name: usage.name, line }),
+            _ => None,
+        }
+    }
+
+    // Symbols of the given kind that are still tracked, i.e. the global ones
+    pub fn remaining(&self, kind: Kind) -> Vec<i32> {
+        let mut ids: Vec<i32> = self.usages.iter()
+            .filter(|(_, usage)| usage.kind == kind)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+        ids
+    }
+}
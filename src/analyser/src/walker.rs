@@ -2,11 +2,17 @@ use spl_ast::tree::*;
 use std::fs::File;
 use std::io::Read;
 use crate::manager::SymbolManager;
-use crate::error::{SemanticError, SemanticErrorManager};
+use crate::error::{SemanticError, SemanticErrorManager, SemanticWarning};
+use crate::flow::FlowChecker;
+use crate::usage::{Kind, Unused, UsageTracker};
+use crate::init::InitTracker;
+use crate::typed::{mangle_overload, resolve_program, Callee, Calls, Inferred};
 use crate::symbol::*;
 use crate::stack::ScopeStack;
-use crate::typer::{TypeChecker, ScopeType};
+use crate::typer::{signature, TypeChecker, ScopeType};
 use spl_lexer::tokens::Span;
+use spl_ast::eval::ConstValue;
+use std::collections::{HashMap, HashSet};
 
 pub struct Walker {
     pub program: Program,
@@ -15,6 +21,29 @@ pub struct Walker {
     pub manager: SymbolManager,
     pub errors: SemanticErrorManager,
     pub typer: TypeChecker,
+    // Generic functions by name, their instantiations waiting to be
+    // checked once the current top level part is done, and the checked
+    // ones by the index of that part
+    generics: HashMap<String, Function>,
+    instances: Vec<(Function, usize)>,
+    instantiated: HashSet<String>,
+    monomorphized: Vec<(usize, Function)>,
+    usage: UsageTracker,
+    init: InitTracker,
+    // Statement being checked, where reads of unassigned variables are reported
+    span: Option<Span>,
+    // Line of the function being declared, and of each generic function
+    decl_line: usize,
+    generic_lines: HashMap<String, usize>,
+    // Types of the `auto` declarations
+    inferred: Inferred,
+    // The function each call refers to, the mangled names of the functions
+    // of the program by their symbol, and the function being checked
+    calls: Calls,
+    overloads: HashMap<i32, String>,
+    caller: String,
+    // Separately compiled modules need no `main`
+    lib: bool,
     verbose: bool
 }
 
@@ -31,6 +60,20 @@ impl Walker {
             errors: SemanticErrorManager::new(),
             symbol_tables: ScopeStack::new(),
             typer: TypeChecker::new(),
+            generics: HashMap::new(),
+            instances: Vec::new(),
+            instantiated: HashSet::new(),
+            monomorphized: Vec::new(),
+            usage: UsageTracker::default(),
+            init: InitTracker::default(),
+            span: None,
+            decl_line: 0,
+            generic_lines: HashMap::new(),
+            inferred: Inferred::new(),
+            calls: Calls::new(),
+            overloads: HashMap::new(),
+            caller: String::new(),
+            lib: false,
             verbose
         }
     }
@@ -43,6 +86,28 @@ impl Walker {
         self.errors.get_errors()
     }
 
+    // The program with the types of `auto` declarations, the overloads
+    // picked by the calls and the instances of generic functions filled in,
+    // as the code generator needs them
+    pub fn typed_program(&self) -> Program {
+        let overloaded = self.overloads.values()
+            .filter_map(|mangled| mangled.split('.').next())
+            .filter(|name| self.symbol_tables.get_func_symbols(&name.to_string()).is_ok_and(|funcs| funcs.len() > 1))
+            .map(str::to_string)
+            .collect();
+        resolve_program(&self.program, &self.inferred, &self.calls, overloaded, &self.monomorphized)
+    }
+
+    pub fn get_warnings(&self) -> &Vec<SemanticWarning> {
+        self.errors.get_warnings()
+    }
+
+    pub fn print_warnings(&self) {
+        for warning in self.errors.get_warnings() {
+            println!("{}", warning);
+        }
+    }
+
     pub fn print_errors(&self) -> Result<(), String> {
         for error in self.errors.get_errors() {
             println!("{}", error.to_string());
@@ -50,6 +115,29 @@ impl Walker {
         if self.errors.get_errors().len() > 0 {
             return Err(format!("{} semantic error(s) found.", self.errors.get_errors().len()));
         }
+        if self.errors.is_werror() && !self.errors.get_warnings().is_empty() {
+            return Err(format!("{} semantic warning(s) treated as errors.", self.errors.get_warnings().len()));
+        }
+        Ok(())
+    }
+
+    // Enable or allow a warning, in the order of the command line
+    pub fn set_lint(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        self.errors.set_lint(name, enabled)
+    }
+
+    pub fn set_lib(&mut self, lib: bool) {
+        self.lib = lib;
+    }
+
+    // Structs are equivalent by name unless the mode is `structural`,
+    // then by the types of their fields
+    pub fn set_struct_equivalence(&mut self, mode: &str) -> Result<(), String> {
+        self.typer.structs = match mode {
+            "name" => None,
+            "structural" => Some(self.symbol_tables.struct_scope.clone()),
+            _ => return Err(format!("Unknown struct equivalence {}, expected name or structural", mode)),
+        };
         Ok(())
     }
 
@@ -58,8 +146,19 @@ impl Walker {
     }
 
     pub fn update_line_with_span(&mut self, span: &Span) {
-        let lineno = self.program_source[..span.start].lines().count();
+        let lineno = self.line_of(span);
         self.errors.update_line_with_value(lineno);
+        self.span = Some(span.clone());
+    }
+
+    fn line_of(&self, span: &Span) -> usize {
+        self.program_source[..span.start].matches('\n').count() + 1
+    }
+
+    // Line and column of the start of a span, e.g. `3:5`
+    fn position_of(&self, span: &Span) -> String {
+        let line_start = self.program_source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        format!("{}:{}", self.line_of(span), self.program_source[line_start..span.start].chars().count() + 1)
     }
 
     pub fn traverse(&mut self) {
@@ -77,9 +176,30 @@ impl Walker {
                     println!("Program");
                 }
                 self.update_line();
-                for part in parts {
+                self.define_builtins();
+                for (i, part) in parts.iter().enumerate() {
                     self.traverse_program_part(part);
+                    while let Some((instance, line)) = self.instances.pop() {
+                        self.decl_line = line;
+                        self.traverse_function(&instance);
+                        self.monomorphized.push((i, instance));
+                    }
+                }
+                let has_main = self.symbol_tables.get_func_symbols(&"main".to_string()).is_ok() || self.generics.contains_key("main");
+                if !self.lib && !has_main {
+                    self.errors.add_error_at(SemanticError::ImproperUsageError {
+                        id: 28,
+                        message: "The program has no main function, compile it with --lib if it is a module".to_owned(),
+                        line: 0,
+                    }, self.program_source.lines().count());
+                }
+                // Globals and functions stay in scope until the end
+                for id in [self.usage.remaining(Kind::Global), self.usage.remaining(Kind::Function)].concat() {
+                    if let Some(unused) = self.usage.release(id) {
+                        self.report_unused(unused);
+                    }
                 }
+                self.errors.sort_warnings();
             }
             Program::Error => {
                 println!("Error in Program");
@@ -87,18 +207,38 @@ impl Walker {
         }
     }
 
+    // Functions provided by the runtime library
+    fn define_builtins(&mut self) {
+        let string = VarType::Primitive(BasicType::String);
+        let builtins = [
+            ("len", BasicType::Int(IntType::INT), vec![string.clone()]),
+            ("stoi", BasicType::Int(IntType::INT), vec![string.clone()]),
+            ("stof", BasicType::Double, vec![string.clone()]),
+            ("itos", BasicType::String, vec![VarType::Primitive(BasicType::Int(IntType::INT))]),
+            ("ftos", BasicType::String, vec![VarType::Primitive(BasicType::Double)]),
+        ];
+        for (name, ret_type, params) in builtins {
+            let func = self.manager.new_func_symbol(name.to_owned(), (ret_type, params), true);
+            self.symbol_tables.define_func_symbol(func).expect("Builtins are defined first");
+        }
+    }
+
     fn traverse_program_part(&mut self, part: &ProgramPart) {
         match part {
             ProgramPart::Statement(statement) => {
                 if self.verbose {
                     println!("Statement");
                 }
+                self.caller.clear();
                 self.traverse_statement(statement);
             }
-            ProgramPart::Function(function) => {
+            ProgramPart::Function(function, span) => {
                 if self.verbose {
                     println!("Function");
                 }
+                // Errors keep the line of the previous part, only the
+                // warnings about the declaration use its own line
+                self.decl_line = self.line_of(span);
                 self.traverse_function(function);
             }
         }
@@ -132,44 +272,63 @@ impl Walker {
         }
     }
 
+    // Indices of an array reference, constant indices are folded so that
+    // they can be bounds checked, any other index is unknown (0)
     fn handle_dimensions(&mut self, dimensions: Vec<CompExpr>) -> Option<Vec<usize>> {
-        if dimensions.len() == 0 {
-            return Some(Vec::new());
+        let mut dim = Vec::new();
+        for (i, comp_expr) in dimensions.iter().enumerate() {
+            let index_type = self.traverse_comp_expr(comp_expr);
+            if index_type.is_error() {
+                dim.push(0);
+            } else if let VarType::Primitive(BasicType::Int(_)) = index_type {
+                match self.eval_const(comp_expr) {
+                    Some(ConstValue::Int(n)) if n < 0 => {
+                        self.errors.add_error(SemanticError::ImproperUsageError {
+                            id: 21,
+                            message: format!("Index {} is out of bounds: {} < 0", i, n),
+                            line: 0,
+                        });
+                        return None;
+                    }
+                    Some(ConstValue::Int(n)) => dim.push(n as usize),
+                    _ => dim.push(0),
+                }
+            } else {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 12,
+                    message: "Array indexing with a non-integer type expression".to_owned(),
+                    line: 0,
+                });
+                return None;
+            }
         }
+        Some(dim)
+    }
 
-        let dim = dimensions.iter()
-            .map(|comp_expr| {
-                match comp_expr {
-                    CompExpr::Value(Value::Integer(value)) => Ok(*value as usize),
-                    _ => {
-                        if let Some(VarType::Primitive(BasicType::Int)) = self.traverse_comp_expr(&comp_expr) {
-                            Ok(0_usize)
-                        } else {
-                            Err(SemanticError::ImproperUsageError {
-                                id: 12,
-                                message: "Array indexing with a non-integer type expression".to_owned(),
-                                line: 0,
-                            })
-                        }
-                    }
+    // Sizes of a declared array, they have to be positive integer constants
+    fn handle_array_size(&mut self, dimensions: Vec<CompExpr>) -> Option<Vec<usize>> {
+        let mut dim = Vec::new();
+        for comp_expr in dimensions.iter() {
+            let size_type = self.traverse_comp_expr(comp_expr);
+            match (size_type, self.eval_const(comp_expr)) {
+                (VarType::Primitive(BasicType::Int(_)), Some(ConstValue::Int(n))) if n > 0 => dim.push(n as usize),
+                (VarType::Primitive(BasicType::Error), _) => return None,
+                _ => {
+                    self.errors.add_error(SemanticError::ImproperUsageError {
+                        id: 12,
+                        message: format!("Array size {} is not a positive integer constant", comp_expr),
+                        line: 0,
+                    });
+                    return None;
                 }
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|err| {
-                self.errors.add_error(err);
-            })
-            .ok()?;
-
-        if dim.len() != dimensions.len() {
-            self.errors.add_error(SemanticError::ImproperUsageError {
-                id: 12,
-                message: "Array indexing with a non-integer type expression".to_owned(),
-                line: 0,
-            });
-            None
-        }else{
-            Some(dim)
+            }
         }
+        Some(dim)
+    }
+
+    fn eval_const(&self, comp_expr: &CompExpr) -> Option<ConstValue> {
+        let symbol_tables = &self.symbol_tables;
+        comp_expr.eval_const(&|name| symbol_tables.get_const(name))
     }
 
     // This is used when doing member assignments.
@@ -219,6 +378,160 @@ impl Walker {
         Some(current_type)
     }
     
+    // Define a variable, warning when it hides one of an outer scope. The
+    // uses of constants are folded, so only the other variables are followed.
+    fn define_var_symbol(&mut self, symbol: VarSymbol, kind: Option<Kind>) -> Result<(), SemanticError> {
+        let line = match kind {
+            Some(Kind::Parameter) => self.decl_line,
+            _ => self.errors.get_line(),
+        };
+        let (id, name) = (symbol.id, symbol.identifier.clone());
+        let shadows = self.symbol_tables.get_var_symbol(&name).is_ok();
+        self.symbol_tables.define_var_symbol(symbol)?;
+        if shadows {
+            self.errors.add_warning_at(SemanticWarning::Shadowing {
+                id: 5,
+                message: format!("Variable {} shadows a variable of an outer scope", name),
+                line: 0,
+            }, line);
+        }
+        if let Some(kind) = kind {
+            self.usage.declare(id, &name, kind, line);
+        }
+        Ok(())
+    }
+
+    fn var_kind(&self) -> Kind {
+        if self.symbol_tables.is_global_scope() {
+            Kind::Global
+        } else {
+            Kind::Local
+        }
+    }
+
+    // The variable read by a reference, e.g. `a` for `a[i]` or `a.b`
+    fn root_symbol(&self, variable: &Variable) -> Option<i32> {
+        let name = match variable {
+            Variable::VarReference(name, _) => name,
+            Variable::StructReference(vars) => match vars.first() {
+                Some(Variable::VarReference(name, _)) => name,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        self.symbol_tables.get_var_symbol(name).ok().map(|symbol| symbol.id)
+    }
+
+    // A read of a variable, which has to be assigned on every path to it.
+    // Structs are tracked as a whole: a read of any field, e.g. `s.x`, needs
+    // an assignment to the struct or to any of its fields before it, even
+    // another one, so that fields left unassigned are not found.
+    fn read_variable(&mut self, variable: &Variable) {
+        let Some(id) = self.root_symbol(variable) else {
+            return;
+        };
+        self.usage.read(id);
+        let name = match variable {
+            Variable::StructReference(vars) => vars[0].get_name(),
+            _ => variable.get_name(),
+        };
+        if let Some(declared) = self.init.check(id).cloned() {
+            let read = self.span.as_ref().map_or(String::new(), |span| self.position_of(span));
+            self.errors.add_warning(SemanticWarning::Uninitialized {
+                id: 7,
+                message: format!("Variable {} declared at {} may be read in the statement at {} before it is assigned", name, self.position_of(&declared), read),
+                line: 0,
+            });
+        }
+    }
+
+    // Taking the address of a variable lets it be read and assigned through
+    // the pointer, e.g. by scanf
+    fn take_address(&mut self, comp_expr: &CompExpr) {
+        if let CompExpr::Variable(variable) = comp_expr {
+            if let Some(id) = self.root_symbol(variable) {
+                self.usage.escape(id);
+                self.init.assign(id);
+            }
+        }
+    }
+
+    // The arguments of printf and scanf are not checked, but whatever they
+    // refer to is still used
+    fn mark_used(&mut self, comp_expr: &CompExpr) {
+        match comp_expr {
+            CompExpr::Variable(Variable::Dereference(expr)) => self.mark_used(expr),
+            CompExpr::Variable(variable) => {
+                let parts = match variable {
+                    Variable::StructReference(vars) => vars.as_slice(),
+                    _ => std::slice::from_ref(variable),
+                };
+                for part in parts {
+                    if let Variable::VarReference(_, dims) = part {
+                        dims.iter().for_each(|dim| self.mark_used(dim));
+                    }
+                }
+                self.read_variable(variable);
+            }
+            CompExpr::UnaryOperation(UnaryOperator::Ref, expr) => {
+                self.take_address(expr);
+                self.mark_used(expr);
+            }
+            CompExpr::UnaryOperation(_, expr) => self.mark_used(expr),
+            CompExpr::BinaryOperation(lhs, _, rhs) => {
+                self.mark_used(lhs);
+                self.mark_used(rhs);
+            }
+            CompExpr::FuncCall(Function::FuncReference(name, args, _)) => {
+                for func in self.symbol_tables.get_func_symbols(name).unwrap_or_default() {
+                    self.usage.read(func.id);
+                }
+                args.iter().for_each(|arg| self.mark_used(arg));
+            }
+            _ => {}
+        }
+    }
+
+    fn report_unused(&mut self, unused: Unused) {
+        let (warning, line) = match unused {
+            Unused::Never { name, kind: Kind::Parameter, line } => (SemanticWarning::UnusedParameter {
+                id: 3,
+                message: format!("Parameter {} is never read", name),
+                line: 0,
+            }, line),
+            Unused::Never { name, kind: Kind::Function, line } => (SemanticWarning::UnusedFunction {
+                id: 4,
+                message: format!("Function {} is never called", name),
+                line: 0,
+            }, line),
+            Unused::Never { name, line, .. } => (SemanticWarning::UnusedVariable {
+                id: 2,
+                message: format!("Variable {} is never read", name),
+                line: 0,
+            }, line),
+            Unused::Assignment { name, line } => (SemanticWarning::UnusedAssignment {
+                id: 6,
+                message: format!("Value assigned to {} is never read", name),
+                line: 0,
+            }, line),
+        };
+        self.errors.add_warning_at(warning, line);
+    }
+
+    // Leave a scope, reporting the variables that were of no use in it
+    fn exit_scope(&mut self) {
+        let mut ids: Vec<i32> = self.symbol_tables.get_current_scope().symbols.values().map(|s| s.id).collect();
+        ids.sort();
+        for id in ids {
+            if let Some(unused) = self.usage.release(id) {
+                self.report_unused(unused);
+            }
+        }
+        if let Err(err) = self.symbol_tables.exit_scope() {
+            self.errors.add_error(err);
+        }
+    }
+
     fn traverse_variable(&mut self, variable: &Variable) -> Option<VarType> {
         match variable {
             Variable::VarReference(name, dimensions) => {
@@ -242,9 +555,9 @@ impl Walker {
                 if self.verbose {
                     println!("VarDeclaration: {:?}, Values: {:?}, Dimensions: {:?}", name, values, dimensions);
                 }
-                let dim = self.handle_dimensions(*dimensions.clone())?;
+                let dim = self.handle_array_size(*dimensions.clone())?;
 
-                let symbol_type = BasicType::from(*values.clone());
+                let symbol_type = self.value_type("Variable", name, BasicType::from(*values.clone()));
                 let var_type = if dim.len() > 0 {
                         VarType::Array((symbol_type, dim))
                     } else {
@@ -254,55 +567,130 @@ impl Walker {
                 let new_symbol = self.manager.new_var_symbol(
                     *name.clone(), 
                     var_type.clone(), 
-                    false,
+                    self.symbol_tables.is_global_scope(),
                 );
+                let id = new_symbol.id;
 
-                match self.symbol_tables.define_var_symbol(new_symbol) {
-                    Ok(()) => Some(var_type),
+                match self.define_var_symbol(new_symbol, Some(self.var_kind())) {
+                    Ok(()) => {
+                        // Globals, arrays and strings start zeroed, other locals
+                        // hold whatever their stack slot held
+                        if !self.symbol_tables.is_global_scope() && matches!(var_type, VarType::Primitive(ref t) if *t != BasicType::String) {
+                            if let Some(span) = self.span.clone() {
+                                self.init.declare(id, span);
+                            }
+                        }
+                        Some(var_type)
+                    }
                     Err(err) => {
                         self.errors.add_error(err);
                         None
                     }
                 }
             }
+            Variable::ConstDeclaration(name, value_type, value) => {
+                if self.verbose {
+                    println!("ConstDeclaration: {:?}, Type: {:?}, Value: {:?}", name, value_type, value);
+                }
+                let var_type = VarType::Primitive(BasicType::from(*value_type.clone()));
+                let new_symbol = self.manager.new_var_symbol(
+                    *name.clone(),
+                    var_type.clone(),
+                    self.symbol_tables.is_global_scope(),
+                );
+                if let Err(err) = self.define_var_symbol(new_symbol, None) {
+                    self.errors.add_error(err);
+                    return None;
+                }
+
+                let right_type = self.traverse_comp_expr(value);
+                if let Err(err) = self.typer.check_assign_operation(var_type.clone(), right_type) {
+                    self.errors.add_error(err);
+                    return None;
+                }
+                self.check_constant(&var_type, value);
+                match self.eval_const(value) {
+                    Some(folded) => {
+                        self.symbol_tables.define_const(name, folded);
+                        Some(var_type)
+                    }
+                    None => {
+                        self.errors.add_error(SemanticError::ImproperUsageError {
+                            id: 23,
+                            message: format!("The value of const variable {} is not a constant expression", name),
+                            line: 0
+                        });
+                        None
+                    }
+                }
+            }
             Variable::VarAssignment(var, val) => {
                 if self.verbose {
                     println!("VarAssignment: {:?}, Value: {:?}", var, val);
                 }
-                // Calculate the type of right hand side
-                let left_type = self.traverse_variable(var)?;
-                let dims = match left_type {
-                    VarType::Array((_, ref d)) => d.clone(),
-                    _ => Vec::new()
+                // Constants cannot be assigned after their declaration
+                let target = match var.as_ref() {
+                    Variable::StructReference(vars) => vars.first(),
+                    var => Some(var),
                 };
-
-                let right_type = if val.len() == 1 {
-                    let first_element = val.first().unwrap();
-                    self.traverse_comp_expr(first_element)?
-                } else {
-                    let array_type = self.traverse_comp_expr(val.first().unwrap()).unwrap();
-                    let assigned_values = val.iter().map(|v| {
-                        let var_type = self.traverse_comp_expr(v).unwrap();
-                        self.typer.check_assign_operation(array_type.clone(), var_type).unwrap()
-                    }).collect::<Vec<VarType>>();
-                    let basic_type = match array_type {
-                        VarType::Primitive(b) => b,
-                        _ => BasicType::Null
-                    };
-                    if assigned_values.len() != dims.iter().product() {
+                if let Some(Variable::VarReference(name, _)) = target {
+                    if self.symbol_tables.get_const(name).is_some() {
                         self.errors.add_error(SemanticError::ImproperUsageError {
-                            id: 14,
-                            message: "Invalid Array Assignment.".to_owned(),
+                            id: 23,
+                            message: format!("Cannot assign to const variable {}", name),
                             line: 0
                         });
                         return None;
                     }
-                    let array_type = VarType::Array((basic_type, dims));
-                    array_type
+                }
+                // Strings are immutable, their characters cannot be assigned
+                if let Variable::VarReference(name, dims) = var.as_ref() {
+                    let is_string = self.symbol_tables.get_var_symbol(name)
+                        .is_ok_and(|s| s.symbol_type == VarType::Primitive(BasicType::String));
+                    if is_string && !dims.is_empty() {
+                        self.errors.add_error(SemanticError::ImproperUsageError {
+                            id: 23,
+                            message: format!("Cannot assign to a character of string {}, strings are immutable", name),
+                            line: 0
+                        });
+                        return None;
+                    }
+                }
+                // Calculate the type of right hand side
+                let left_type = self.traverse_variable(var).unwrap_or_else(VarType::error);
+
+                let right_type = match val.as_slice() {
+                    [CompExpr::InitList(items)] => {
+                        self.check_initializer(left_type.clone(), items).map(|_| left_type.clone())
+                    }
+                    _ => val.first().map(|value| self.traverse_comp_expr(value))
                 };
+                // The variable holds a value from here on, even a mistyped one,
+                // and a struct once any of its fields does
+                let whole = match var.as_ref() {
+                    Variable::VarReference(_, dims) => dims.is_empty(),
+                    Variable::StructReference(_) => true,
+                    _ => false,
+                };
+                if let Some(id) = self.root_symbol(var).filter(|_| whole) {
+                    self.init.assign(id);
+                }
+                let right_type = right_type?;
                 
-                match self.typer.check_assign_operation(left_type, right_type) {
-                    Ok(t) => Some(t),
+                match self.typer.check_assign_operation(left_type.clone(), right_type) {
+                    Ok(t) => {
+                        if let [value] = val.as_slice() {
+                            self.check_constant(&left_type, value);
+                        }
+                        // Only an assignment of the whole variable replaces its value
+                        if let Variable::VarReference(name, dims) = var.as_ref() {
+                            let id = self.root_symbol(var).filter(|_| dims.is_empty());
+                            if let Some(previous) = id.and_then(|id| self.usage.write(id, self.errors.get_line())) {
+                                self.report_unused(Unused::Assignment { name: *name.clone(), line: previous });
+                            }
+                        }
+                        Some(t)
+                    }
                     Err(err) => {
                         self.errors.add_error(err);
                         None
@@ -315,8 +703,19 @@ impl Walker {
                 if self.verbose {
                     println!("StructDefinition: {:?}", name);
                 }
+                // Declared first so that pointer fields can refer to the struct itself
+                let forward_declared = self.symbol_tables.is_struct_declared(name);
+                self.symbol_tables.declare_struct(name);
                 let mut vars: Vec<(String, VarType)> = Vec::new();
+                // The initializer of an `auto` field is reported with its declaration
+                let inferred = variables.iter().filter_map(|var| match var {
+                    Variable::VarDeclaration(name, ty, _) if **ty == Value::Auto => Some(name.to_string()),
+                    _ => None,
+                }).collect::<Vec<String>>();
                 for var in *variables.clone() {
+                    if matches!(&var, Variable::VarAssignment(field, _) if inferred.contains(&field.get_name())) {
+                        continue;
+                    }
                     if let Some(var_type) = self.traverse_struct_field(&var) {
                         vars.push(var_type);
                     }
@@ -324,16 +723,27 @@ impl Walker {
                 match self.symbol_tables.define_struct((*name.clone(), vars)) {
                     Ok(()) => None,
                     Err(err) => {
+                        // A failed definition should not leave the struct declared
+                        if !forward_declared {
+                            self.symbol_tables.forward_structs.borrow_mut().remove(name.as_ref());
+                        }
                         self.errors.add_error(err);
                         None
                     }
                 }
             }
+            Variable::StructForward(name) => {
+                if self.verbose {
+                    println!("StructForward: {:?}", name);
+                }
+                self.symbol_tables.declare_struct(name);
+                None
+            }
             // Define a variable
             // First check if the struct exists
             // Then check if the variable is valid
             Variable::StructDeclaration(obj_type, name, dim) => {
-                let dimensions = self.handle_dimensions(*dim.clone()).unwrap_or(Vec::new());
+                let dimensions = self.handle_array_size(*dim.clone())?;
 
                 match self.symbol_tables.get_struct(obj_type) {
                     Ok(struct_type) => {
@@ -344,16 +754,25 @@ impl Walker {
                             } else {
                                 VarType::Array((BasicType::Struct(struct_type.0.clone()), dimensions.clone()))
                             },
-                            false,
+                            self.symbol_tables.is_global_scope(),
                         );
-                        match self.symbol_tables.define_var_symbol(new_symbol) {
-                            Ok(()) => Some(
-                                if dimensions.is_empty() {
-                                    VarType::Primitive(BasicType::Struct(struct_type.0.clone()))
-                                } else {
-                                    VarType::Array((BasicType::Struct(struct_type.0.clone()), dimensions.clone()))
+                        let id = new_symbol.id;
+                        match self.define_var_symbol(new_symbol, Some(self.var_kind())) {
+                            Ok(()) => {
+                                // Like other scalars, a local struct starts out unassigned
+                                if dimensions.is_empty() && !self.symbol_tables.is_global_scope() {
+                                    if let Some(span) = self.span.clone() {
+                                        self.init.declare(id, span);
+                                    }
                                 }
-                            ),
+                                Some(
+                                    if dimensions.is_empty() {
+                                        VarType::Primitive(BasicType::Struct(struct_type.0.clone()))
+                                    } else {
+                                        VarType::Array((BasicType::Struct(struct_type.0.clone()), dimensions.clone()))
+                                    }
+                                )
+                            }
                             Err(err) => {
                                 self.errors.add_error(err);
                                 None
@@ -369,25 +788,24 @@ impl Walker {
                 if vars.is_empty() {
                     return None;
                 }
-                // The first var reference is the reference for the struct variables
-                let struct_var = match vars[0].clone() {
+                // The first var reference is the reference for the struct variables,
+                // or the dereference of a struct pointer when accessed by `->`
+                let var_type = match vars[0].clone() {
                     Variable::VarReference(name, dimension) => {
                         let dim = self.handle_dimensions(*dimension).unwrap_or(Vec::new());
-                        (*name, dim)
+                        let struct_symbol = self.symbol_tables.get_var_symbol(&name).map_err(|err| {
+                                self.errors.add_error(err);
+                        }).ok()?; 
+
+                        // BasicType Struct or Array
+                        self.typer.check_type(struct_symbol.symbol_type.clone(), &dim).map_err(|err| {
+                            self.errors.add_error(err);
+                        }).ok()?
                     },
+                    Variable::Dereference(_) => self.traverse_variable(&vars[0])?,
                     _ => return None
                 };
 
-                let struct_symbol = self.symbol_tables.get_var_symbol(&struct_var.0).map_err(|err| {
-                        self.errors.add_error(err);
-                }).ok()?; 
-                
-                let mut var_type = struct_symbol.symbol_type.clone(); // BasicType Struct or Array
-
-                var_type = self.typer.check_type(var_type, &struct_var.1).map_err(|err| {
-                    self.errors.add_error(err);
-                }).ok()?;
-
                 // The rest of the var references are the struct members
                 let members = vars[1..].iter().map(|v|
                     match v {
@@ -419,7 +837,9 @@ impl Walker {
                 if self.verbose {
                     println!("FormalParameter: {:?}, Values: {:?}, Dimensions: {:?}", name, values, dimensions);
                 }
-                let symbol_type = BasicType::from(*values.clone());
+                // Structs passed by value must be complete
+                let symbol_type = self.check_type_defined(values)?;
+                let symbol_type = self.value_type("Parameter", name, symbol_type);
                 let var_type = |dimensions: &[usize]| -> VarType {
                     if !dimensions.is_empty() {
                         VarType::Array((symbol_type.clone(), dimensions.to_vec()))
@@ -434,7 +854,7 @@ impl Walker {
                     false,
                 );
 
-                match self.symbol_tables.define_var_symbol(new_symbol) {
+                match self.define_var_symbol(new_symbol, Some(Kind::Parameter)) {
                     Ok(()) => Some(var_type(&dimensions)),
                     Err(err) => {
                         self.errors.add_error(err);
@@ -442,15 +862,291 @@ impl Walker {
                     }
                 }
             }
+            Variable::Dereference(expr) => {
+                if self.verbose {
+                    println!("Dereference: {:?}", expr);
+                }
+                let var_type = self.traverse_comp_expr(expr);
+                self.handle_dereference(var_type)
+            }
             Variable::Error => None
         }
     }
 
+    // A declaration whose type is that of its initializer. The variable is
+    // declared even when the type cannot be inferred, with the error type
+    // so that its uses are not reported again.
+    fn traverse_auto(&mut self, name: &str, dims: &[CompExpr], init: Option<&Variable>, span: &Span) {
+        if self.verbose {
+            println!("AutoDeclaration: {:?}, Dimensions: {:?}", name, dims);
+        }
+        let value = match init {
+            Some(Variable::VarAssignment(_, value)) => value.as_slice(),
+            _ => &[],
+        };
+        let inferred = match value {
+            [CompExpr::InitList(_)] => Err(format!("Cannot infer the type of {} from an initializer list", name)),
+            [value] => match self.traverse_comp_expr(value) {
+                _ if !dims.is_empty() => Err(format!("Cannot infer the type of array {}", name)),
+                VarType::Primitive(BasicType::Pointer(t)) if *t == BasicType::Null =>
+                    Err(format!("Cannot infer the type of {} from null", name)),
+                VarType::Array(_) => Err(format!("Cannot infer the type of {} from an array", name)),
+                var_type => Ok(var_type),
+            },
+            _ => Err(format!("Cannot infer the type of {} without an initializer", name)),
+        };
+        let var_type = inferred.unwrap_or_else(|message| {
+            self.errors.add_error(SemanticError::ImproperUsageError {
+                id: 27,
+                message,
+                line: 0,
+            });
+            VarType::error()
+        });
+        if let VarType::Primitive(ref t) = var_type {
+            if !var_type.is_error() {
+                self.inferred.insert((span.start, self.caller.clone(), name.to_string()), Value::from(t.clone()));
+            }
+        }
+
+        let new_symbol = self.manager.new_var_symbol(name.to_string(), var_type, false);
+        let id = new_symbol.id;
+        if let Err(err) = self.define_var_symbol(new_symbol, Some(self.var_kind())) {
+            self.errors.add_error(err);
+            return;
+        }
+        if init.is_some() {
+            self.init.assign(id);
+            self.usage.write(id, self.errors.get_line());
+        }
+    }
+
+    fn handle_dereference(&mut self, var_type: VarType) -> Option<VarType> {
+        match var_type {
+            VarType::Primitive(BasicType::Pointer(t)) if *t != BasicType::Null => Some(VarType::Primitive(*t)),
+            VarType::Primitive(BasicType::Error) => Some(var_type),
+            _ => {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 11,
+                    message: "Invalid Dereference Operation.".to_owned(),
+                    line: 0
+                });
+                None
+            }
+        }
+    }
+
+    // The type of a variable, parameter or field, which has to hold values.
+    // A void one is reported and gets the error type.
+    fn value_type(&mut self, what: &str, name: &str, ty: BasicType) -> BasicType {
+        if ty != BasicType::Void {
+            return ty;
+        }
+        self.errors.add_error(SemanticError::ImproperUsageError {
+            id: 29,
+            message: format!("{} {} cannot have type Void", what, name),
+            line: 0
+        });
+        BasicType::Error
+    }
+
+    // Structs used by value must be complete, structs behind pointers
+    // only need to be declared
+    fn check_type_defined(&mut self, ty: &Value) -> Option<BasicType> {
+        let basic_type = BasicType::from(ty.clone());
+        match &basic_type {
+            BasicType::Struct(obj) => {
+                if let Err(err) = self.symbol_tables.get_struct(obj) {
+                    self.errors.add_error(err);
+                    return None;
+                }
+            }
+            BasicType::Pointer(_) => {
+                let mut inner = &basic_type;
+                while let BasicType::Pointer(t) = inner {
+                    inner = t;
+                }
+                if let BasicType::Struct(obj) = inner {
+                    if !self.symbol_tables.is_struct_declared(obj) {
+                        self.errors.add_error(SemanticError::ReferenceError {
+                            id: 14,
+                            variable: obj.clone(),
+                            line: 0,
+                        });
+                        return None;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Some(basic_type)
+    }
+
+    // Check a braced initializer of the given type. Like in C, nested braces
+    // may be elided, designators pick struct fields and missing elements are
+    // zero filled, so only excess elements and mismatched types are errors.
+    fn check_initializer(&mut self, ty: VarType, items: &[CompExpr]) -> Option<()> {
+        match ty {
+            VarType::Array(_) | VarType::Primitive(BasicType::Struct(_)) => {
+                self.check_aggregate_initializer(&ty, items, &mut 0, true)
+            }
+            // The shape is unknown, only the items themselves are checked
+            VarType::Primitive(BasicType::Error) => {
+                for item in items {
+                    match item {
+                        CompExpr::InitList(sub) => self.check_initializer(VarType::error(), sub)?,
+                        CompExpr::Designated(_, value) => self.check_initializer(VarType::error(), std::slice::from_ref(value.as_ref()))?,
+                        _ => {
+                            self.traverse_comp_expr(item);
+                        }
+                    }
+                }
+                Some(())
+            }
+            // A scalar may be wrapped in braces as well
+            _ => match items {
+                [] => Some(()),
+                [item @ (CompExpr::InitList(_) | CompExpr::Designated(_, _))] | [_, item, ..] => {
+                    self.errors.add_error(SemanticError::ImproperUsageError {
+                        id: 14,
+                        message: format!("Invalid initializer {} for a value of type {}.", item, ty),
+                        line: 0
+                    });
+                    None
+                }
+                [item] => self.check_initializer_element(ty, item),
+            }
+        }
+    }
+
+    // Walk the elements of an array or struct, consuming items from `pos`.
+    // Without braces only as many items as the aggregate holds are taken.
+    fn check_aggregate_initializer(&mut self, ty: &VarType, items: &[CompExpr], pos: &mut usize, braced: bool) -> Option<()> {
+        let fields = match ty {
+            VarType::Array((t, dims)) => {
+                let element = if dims.len() > 1 {
+                    VarType::Array((t.clone(), dims[1..].to_vec()))
+                } else {
+                    VarType::Primitive(t.clone())
+                };
+                vec![(String::new(), element); dims[0]]
+            }
+            VarType::Primitive(BasicType::Struct(name)) => {
+                self.symbol_tables.get_struct(name).map_err(|err| {
+                    self.errors.add_error(err);
+                }).ok()?.1
+            }
+            _ => return self.check_initializer_element(ty.clone(), &items[*pos]).map(|_| *pos += 1),
+        };
+
+        let mut cursor = 0;
+        while *pos < items.len() {
+            let item = &items[*pos];
+            if let CompExpr::Designated(field, value) = item {
+                // A designator always refers to the innermost braced object
+                if !braced {
+                    break;
+                }
+                if let VarType::Array(_) = ty {
+                    self.errors.add_error(SemanticError::ImproperUsageError {
+                        id: 14,
+                        message: format!("Designator .{} cannot be used to initialize a value of type {}.", field, ty),
+                        line: 0
+                    });
+                    return None;
+                }
+                self.typer.check_struct_field(field, &fields).map_err(|err| {
+                    self.errors.add_error(err);
+                }).ok()?;
+                cursor = fields.iter().position(|(name, _)| name == field.as_ref()).unwrap();
+                *pos += 1;
+                match value.as_ref() {
+                    CompExpr::InitList(sub) => self.check_initializer(fields[cursor].1.clone(), sub)?,
+                    _ => self.check_aggregate_initializer(&fields[cursor].1, std::slice::from_ref(value.as_ref()), &mut 0, false)?,
+                }
+                cursor += 1;
+                continue;
+            }
+            if cursor >= fields.len() {
+                if !braced {
+                    break;
+                }
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 14,
+                    message: format!("Excess elements in the initializer of type {}, expected at most {}.", ty, fields.len()),
+                    line: 0
+                });
+                return None;
+            }
+            let element = fields[cursor].1.clone();
+            match (item, &element) {
+                (CompExpr::InitList(sub), _) => {
+                    *pos += 1;
+                    self.check_initializer(element, sub)?;
+                }
+                (_, VarType::Array(_)) => {
+                    self.check_aggregate_initializer(&element, items, pos, false)?;
+                }
+                (_, VarType::Primitive(BasicType::Struct(_))) => {
+                    // A whole struct value initializes the element directly
+                    let item_type = self.traverse_comp_expr(item);
+                    if item_type == element {
+                        *pos += 1;
+                    } else {
+                        self.check_aggregate_initializer(&element, items, pos, false)?;
+                    }
+                }
+                _ => {
+                    *pos += 1;
+                    self.check_initializer_element(element, item)?;
+                }
+            }
+            cursor += 1;
+        }
+        Some(())
+    }
+
+    fn check_initializer_element(&mut self, ty: VarType, item: &CompExpr) -> Option<()> {
+        let item_type = self.traverse_comp_expr(item);
+        self.typer.check_assign_operation(ty.clone(), item_type).map_err(|err| {
+            self.errors.add_error(err);
+        }).ok()?;
+        self.check_constant(&ty, item);
+        Some(())
+    }
+
+    // Integers convert implicitly between widths, a constant that does not
+    // fit its target wraps around, e.g. `byte b = 300;` stores 44
+    fn check_constant(&mut self, ty: &VarType, value: &CompExpr) {
+        let VarType::Primitive(BasicType::Int(target)) = ty else {
+            return;
+        };
+        if let Some(ConstValue::Int(n)) = self.eval_const(value) {
+            let wrapped = target.wrap(n as i128);
+            if wrapped != n as i128 {
+                self.errors.add_warning(SemanticWarning::Overflow {
+                    id: 8,
+                    message: format!("Constant {} does not fit in {}, it becomes {}", n, target.name(), wrapped),
+                    line: 0,
+                });
+            }
+        }
+    }
+
     fn traverse_struct_field(&mut self, field: &Variable) -> Option<(String, VarType)> {
         match field {
+            Variable::VarDeclaration(varname, type_t, _) if **type_t == Value::Auto => {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 27,
+                    message: format!("Cannot infer the type of field {}", varname),
+                    line: 0,
+                });
+                Some((*varname.clone(), VarType::error()))
+            }
             Variable::VarDeclaration(varname, type_t, offsets) => {
-                let dim = self.handle_dimensions(*offsets.clone()).unwrap_or(Vec::new());
-                let symbol_type = BasicType::from(*type_t.clone());
+                let dim = self.handle_array_size(*offsets.clone())?;
+                let symbol_type = self.check_type_defined(type_t)?;
+                let symbol_type = self.value_type("Field", varname, symbol_type);
                 let var: Option<(String, VarType)> = if dim.len() > 0 {
                     Some((*varname.clone(), VarType::Array((symbol_type, dim))))
                 } else {
@@ -459,7 +1155,7 @@ impl Walker {
                 var
             }
             Variable::StructDeclaration(type_t, identifier, dim) => {
-                let dimensions = self.handle_dimensions(*dim.clone()).unwrap_or(Vec::new());
+                let dimensions = self.handle_array_size(*dim.clone())?;
                 match self.symbol_tables.get_struct(type_t) {
                     Ok(struct_type) => {
                         let var_type = if dimensions.is_empty() {
@@ -475,35 +1171,57 @@ impl Walker {
                     }
                 }
             }
+            // Fields have no values of their own, they are set through the
+            // struct. The field itself is defined by its declaration before.
+            Variable::VarAssignment(var, _) => {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 30,
+                    message: format!("Field {} cannot have an initializer", var.get_name()),
+                    line: 0,
+                });
+                None
+            }
+            Variable::ConstDeclaration(name, type_t, _) => {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 30,
+                    message: format!("Field {} cannot be a constant", name),
+                    line: 0,
+                });
+                self.traverse_struct_field(&Variable::VarDeclaration(name.clone(), type_t.clone(), Box::default()))
+            }
             _ => None
         }
     }
 
     fn traverse_function(&mut self, function: &Function) -> Option<FuncType>{
         match function {
-            Function::FuncReference(name, params) => {
+            Function::FuncReference(name, params, span) => {
                 if self.verbose{
                     println!("FuncReference: {:?}, Params: {:?}", name, params);
                 }
                 let mut args: Vec<VarType> = Vec::new();
                 for param in params {
-                    if let Some(arg) = self.traverse_comp_expr(param) {
-                        args.push(arg);
-                    }else{
-                        args.push(VarType::Primitive(BasicType::Null));
-                    }
+                    args.push(self.traverse_comp_expr(param));
                 }
                 // println!("-> Travesing functions arguments: {:?}", args);
-                let func_symbol = self.symbol_tables.get_func_symbol(name).map_err(|err| {
+                if let Some(generic) = self.generics.get(name.as_str()).cloned() {
+                    return self.instantiate(&generic, args, span);
+                }
+                let overloads = self.symbol_tables.get_func_symbols(name).map_err(|err| {
                     self.errors.add_error(err);
                 }).ok()?;
-                match self.typer.check_func_params(func_symbol.symbol_type.clone().1, args) {
-                    Ok(()) => {
-                        return Some(func_symbol.symbol_type.clone());
+                match self.typer.resolve_overload(name, &overloads, args) {
+                    Ok(func) => {
+                        self.usage.read(func.id);
+                        if let Some(mangled) = self.overloads.get(&func.id) {
+                            let callee = Callee::Function(name.to_string(), mangled.clone());
+                            self.calls.insert((span.start, self.caller.clone()), callee);
+                        }
+                        Some(func.symbol_type)
                     }
                     Err(err) => {
                         self.errors.add_error(err);
-                        return None;
+                        None
                     }
                 }
             }
@@ -512,9 +1230,17 @@ impl Walker {
                 if self.verbose {
                     println!("FuncDeclaration: {:?}, Inputs: {:?}, Output: {:?}", name, inputs, output);
                 }
+                if self.generics.contains_key(name.as_str()) {
+                    self.errors.add_error(SemanticError::RedefinitionError {
+                        id: 4,
+                        variable: *name.clone(),
+                        line: 0,
+                    });
+                    return None;
+                }
                 self.symbol_tables.extend_scope();
                 
-                let ret_type: BasicType = BasicType::from(*output.clone());
+                let ret_type: BasicType = self.check_type_defined(output).unwrap_or(BasicType::from(*output.clone()));
                 let mut params: Vec<VarType> = Vec::new();
                 for param in inputs {
                     if let Some(var_type) = self.traverse_variable(param) {
@@ -523,24 +1249,67 @@ impl Walker {
                 }
                 // println!("-> Declaring parameters: {:?}", self.symbol_tables.get_current_scope());
                 // println!("-> Travesing functions parameters: {:?}", params);
+                if name.as_str() == "main" && !self.lib && !self.check_main(&ret_type, &params) {
+                    self.symbol_tables.exit_scope().ok();
+                    return None;
+                }
                 let func = self.manager.new_func_symbol(*name.clone(), (ret_type, params), true);
+                let id = func.id;
                 match self.symbol_tables.define_func_symbol(func) {
-                    Ok(()) => {}
+                    Ok(()) => {
+                        self.overloads.insert(id, mangle_overload(name, inputs));
+                    }
                     Err(err) => {
                         self.errors.add_error(err);
                         return None;
                     }
                 }
+                // Instances of generic functions only exist because they are called,
+                // and the functions of a module are called from the others it is linked with
+                if name.as_str() != "main" && !self.lib && !self.instantiated.contains(name.as_str()) {
+                    self.usage.declare(id, name, Kind::Function, self.decl_line);
+                }
 
+                let prev_scope = self.typer.set_scope(ScopeType::Func);
+                self.caller = name.to_string();
+                self.init.enter_function();
+                self.typer.enter_function(body);
                 self.traverse_body(body);
+                self.check_flow(name, output, body);
 
-                match self.symbol_tables.exit_scope() {
-                    Ok(()) => {}
-                    Err(err) => {
-                        self.errors.add_error(err)
+                self.exit_scope();
+                self.typer.reset_ret_type();
+                self.typer.set_scope(prev_scope);
+                return None;
+            }
+            Function::Generic(types, decl) => {
+                if self.verbose {
+                    println!("Generic: {:?}, Declaration: {:?}", types, decl);
+                }
+                if let Function::FuncDeclaration(name, inputs, output, _) = decl.as_ref() {
+                    if self.generics.contains_key(name.as_str()) || self.symbol_tables.get_func_symbols(name).is_ok() {
+                        self.errors.add_error(SemanticError::RedefinitionError {
+                            id: 4,
+                            variable: *name.clone(),
+                            line: 0,
+                        });
+                        return None;
                     }
+                    if name.as_str() == "main" && !self.lib {
+                        self.errors.add_error_at(SemanticError::ImproperUsageError {
+                            id: 28,
+                            message: "Function main cannot be generic".to_owned(),
+                            line: 0,
+                        }, self.decl_line);
+                        return None;
+                    }
+                    if let Err(err) = self.typer.check_type_params(name, types, inputs, output) {
+                        self.errors.add_error(err);
+                        return None;
+                    }
+                    self.generics.insert(*name.clone(), function.clone());
+                    self.generic_lines.insert(*name.clone(), self.decl_line);
                 }
-                self.typer.reset_ret_type();
                 return None;
             }
             Function::Error => {
@@ -549,6 +1318,109 @@ impl Walker {
         }
     }
 
+    // The entry point is defined once, as `int main()` or
+    // `int main(int argc, char* argv[])`. A second definition is dropped,
+    // a wrong signature is reported but the body is still checked.
+    fn check_main(&mut self, ret_type: &BasicType, params: &[VarType]) -> bool {
+        let error = |message: String| SemanticError::ImproperUsageError {
+            id: 28,
+            message,
+            line: 0,
+        };
+        if self.symbol_tables.get_func_symbols(&"main".to_string()).is_ok() {
+            self.errors.add_error_at(error("Function main is defined more than once".to_owned()), self.decl_line);
+            return false;
+        }
+        if *ret_type != BasicType::Int(IntType::INT) && *ret_type != BasicType::Error {
+            self.errors.add_error_at(error(format!("Function main should return Int, found {}", ret_type)), self.decl_line);
+        }
+        let argv = BasicType::Pointer(Box::new(BasicType::Char));
+        let valid = match params {
+            [] => true,
+            [VarType::Primitive(BasicType::Int(argc)), argv_type] if *argc == IntType::INT => {
+                *argv_type == VarType::Array((argv.clone(), vec![0]))
+                    || *argv_type == VarType::Primitive(BasicType::Pointer(Box::new(argv)))
+            }
+            _ => false,
+        };
+        if !valid {
+            self.errors.add_error_at(error(format!(
+                "Function main should take no parameters or (int argc, char* argv[]), found {}", signature(params)
+            )), self.decl_line);
+        }
+        true
+    }
+
+    // Statements after a return, break or continue are never executed, and a
+    // function with a result must not reach the end of its body, except for
+    // `main` which returns 0 like in C
+    fn check_flow(&mut self, name: &str, output: &Value, body: &Body) {
+        let mut flow = FlowChecker::default();
+        let falls_off = flow.check_function(body);
+        for (span, exit) in flow.unreachable {
+            self.update_line_with_span(&span);
+            self.errors.add_warning(SemanticWarning::UnreachableCode {
+                id: 1,
+                message: format!("Statement after {} is never executed", exit),
+                line: 0,
+            });
+        }
+        if falls_off && *output != Value::Null && name != "main" {
+            // Reported at the last statement, after which the value is missing
+            if let Body::Body(exprs) = body {
+                if let Some(span) = exprs.last().and_then(|expr| expr.span()) {
+                    self.update_line_with_span(span);
+                }
+            }
+            self.errors.add_error(SemanticError::ImproperUsageError {
+                id: 26,
+                message: format!("Not all paths of function {} return a value", name),
+                line: 0,
+            });
+        }
+    }
+
+    // A call of a generic function, the types of the arguments pick the
+    // instantiation. Its body is checked after the current top level part.
+    fn instantiate(&mut self, generic: &Function, args: Vec<VarType>, span: &Span) -> Option<FuncType> {
+        // The types of failed arguments cannot pick an instantiation
+        if args.iter().any(VarType::is_error) {
+            return None;
+        }
+        let arg_types: Vec<Value> = args.iter().map(|arg| match arg {
+            VarType::Primitive(t) | VarType::Array((t, _)) => Value::from(t.clone()),
+        }).collect();
+        let bindings = generic.infer(&arg_types).map_err(|message| {
+            self.errors.add_error(SemanticError::ImproperUsageError {
+                id: 24,
+                message,
+                line: 0,
+            });
+        }).ok()?;
+        let instance = generic.instantiate(&bindings);
+        let Function::FuncDeclaration(name, inputs, output, _) = &instance else {
+            return None;
+        };
+        let params: Vec<VarType> = inputs.iter().filter_map(|input| match input {
+            Variable::FormalParameter(_, ty, dims) if dims.is_empty() => Some(VarType::Primitive(BasicType::from(*ty.clone()))),
+            Variable::FormalParameter(_, ty, dims) => Some(VarType::Array((BasicType::from(*ty.clone()), *dims.clone()))),
+            _ => None,
+        }).collect();
+        if let Err(err) = self.typer.check_func_params(params.clone(), args) {
+            self.errors.add_error(err);
+            return None;
+        }
+        let func_type = (BasicType::from(*output.clone()), params);
+        self.calls.insert((span.start, self.caller.clone()), Callee::Instance(name.to_string()));
+        if self.instantiated.insert(*name.clone()) {
+            // Mangled names start with the name of the generic function
+            let generic_name = name.split('.').next().unwrap_or_default();
+            let line = self.generic_lines.get(generic_name).copied().unwrap_or(self.decl_line);
+            self.instances.push((instance, line));
+        }
+        Some(func_type)
+    }
+
     fn traverse_body(&mut self, body: &Body) {
         match body {
             Body::Body(exprs) => {
@@ -556,15 +1428,12 @@ impl Walker {
                     println!("Body");
                 }
                 self.symbol_tables.extend_scope();
+                self.usage.settle();
                 for expr in exprs {
                     self.traverse_expr(expr);
                 }
-                match self.symbol_tables.exit_scope() {
-                    Ok(()) => {}
-                    Err(err) => {
-                        self.errors.add_error(err)
-                    }
-                }
+                self.exit_scope();
+                self.usage.settle();
             }
             Body::Error => println!("Error in Body"),
         }
@@ -579,20 +1448,32 @@ impl Walker {
                 self.update_line_with_span(span);
                 self.traverse_if(if_expr);
             }
-            Expr::Loop(loop_expr, span) => {
+            Expr::Loop(loop_expr, label, span) => {
                 if self.verbose {
                     println!("Loop Expression");
                 }
                 self.update_line_with_span(span);
-                self.traverse_loop(loop_expr);
+                self.traverse_loop(loop_expr, label);
             }
             Expr::VarManagement(vars, span) => {
                 if self.verbose {
                     println!("VarManagement");
                 }
                 self.update_line_with_span(span);
-                for var in vars {
-                    self.traverse_variable(var);
+                let mut vars = vars.iter().peekable();
+                while let Some(var) = vars.next() {
+                    match var {
+                        Variable::VarDeclaration(name, ty, dims) if **ty == Value::Auto => {
+                            // The grammar puts the initializer right after the declaration
+                            let init = vars.next_if(|next| matches!(next,
+                                Variable::VarAssignment(target, _) if matches!(target.as_ref(), Variable::VarReference(n, _) if n == name)
+                            ));
+                            self.traverse_auto(name, dims, init, span);
+                        }
+                        var => {
+                            self.traverse_variable(var);
+                        }
+                    }
                 }
             }
             Expr::FuncCall(function, span) => {
@@ -601,8 +1482,9 @@ impl Walker {
                 }
                 self.update_line_with_span(span);
                 match function {
-                    Function::FuncReference(name, _params) => {
+                    Function::FuncReference(name, params, _) => {
                         if (*name).as_str().eq("printf") || (*name).as_str().eq("scanf") {
+                            params.iter().for_each(|param| self.mark_used(param));
                             return;
                         }
                     }
@@ -610,48 +1492,50 @@ impl Walker {
                 }
                 self.traverse_function(function);
             }
-            Expr::Break(span) => {
+            Expr::Break(label, span) => {
                 if self.verbose {
                     println!("Break");
                 }
                 self.update_line_with_span(span);
-                if self.typer.get_scope() != ScopeType::LoopExpr{
-                    self.errors.add_error(SemanticError::ImproperUsageError { 
-                        id: 17, 
-                        message: "Continue and break should only appear in while loop or for loop".to_owned(), 
-                        line: 0
-                    });
+                if let Err(err) = self.typer.check_jump(label) {
+                    self.errors.add_error(err);
                 }
+                self.init.break_loop(label);
             },
-            Expr::Continue(span) => {
+            Expr::Continue(label, span) => {
                 if self.verbose {
                     println!("Continue");
                 }
                 self.update_line_with_span(span);
-                if self.typer.get_scope() != ScopeType::LoopExpr{
-                    self.errors.add_error(SemanticError::ImproperUsageError { 
-                        id: 17, 
-                        message: "Continue and break should only appear in while loop or for loop".to_owned(), 
-                        line: 0
-                    });
+                if let Err(err) = self.typer.check_jump(label) {
+                    self.errors.add_error(err);
                 }
+                self.init.leave();
             },
             Expr::Return(comp_expr, span) => {
                 if self.verbose {
                     println!("Return");
                 }
                 self.update_line_with_span(span);
-                match self.traverse_comp_expr(comp_expr) {
-                    Some(t) => {
-                        let b = match t{
-                            VarType::Primitive(b) => b,
-                            _ => BasicType::Null
-                        };
-                        if let Err(err) = self.typer.check_ret_type(b) {
-                            self.errors.add_error(err);
-                        }
-                    }
-                    None => {}
+                let b = match self.traverse_comp_expr(comp_expr) {
+                    VarType::Primitive(b) => b,
+                    _ => BasicType::Null
+                };
+                let ret_type = VarType::Primitive(self.typer.func_ret_type.clone());
+                match self.typer.check_ret_type(b) {
+                    Ok(()) => self.check_constant(&ret_type, comp_expr),
+                    Err(err) => self.errors.add_error(err),
+                }
+                self.init.leave();
+            }
+            Expr::Delete(comp_expr, span) => {
+                if self.verbose {
+                    println!("Delete");
+                }
+                self.update_line_with_span(span);
+                let t = self.traverse_comp_expr(comp_expr);
+                if let Err(err) = self.typer.check_delete(t) {
+                    self.errors.add_error(err);
                 }
             }
             Expr::Body(body, span) => {
@@ -672,31 +1556,42 @@ impl Walker {
                     println!("IfExpr");
                 }
                 self.traverse_cond_expr(cond);
+                let skipped = self.init.snapshot();
                 self.traverse_body(body);
+                self.init.merge(skipped);
             }
             If::IfElseExpr(cond, then_body, else_body) => {
                 if self.verbose {
                     println!("IfElseExpr");
                 }
                 self.traverse_cond_expr(cond);
+                let before = self.init.snapshot();
                 self.traverse_body(then_body);
+                let then_assigned = self.init.snapshot();
+                self.init.restore(before);
                 self.traverse_body(else_body);
+                self.init.merge(then_assigned);
             }
             If::Error => println!("Error in If"),
         }
     }
 
-    fn traverse_loop(&mut self, loop_expr: &Loop) {
+    fn traverse_loop(&mut self, loop_expr: &Loop, label: &Option<String>) {
         match loop_expr {
             Loop::WhileExpr(cond, body) => {
                 if self.verbose {
                     println!("WhileExpr");
                 }
                 self.traverse_cond_expr(cond);
-                
-                let prev_scope = self.typer.set_scope(ScopeType::LoopExpr);
+                let ended = self.loop_ended(cond);
+
+                if let Err(err) = self.typer.enter_loop(label.clone()) {
+                    self.errors.add_error(err);
+                }
+                self.init.enter_loop(label);
                 self.traverse_body(body);
-                self.typer.set_scope(prev_scope);
+                self.init.exit_loop(ended);
+                self.typer.exit_loop();
             }
             Loop::ForExpr(init, cond, increment, body) => {
                 if self.verbose {
@@ -704,16 +1599,30 @@ impl Walker {
                 }
                 self.traverse_expr(init);
                 self.traverse_cond_expr(cond);
+                let ended = self.loop_ended(cond);
+                self.init.enter_loop(label);
                 self.traverse_expr(increment);
 
-                let prev_scope = self.typer.set_scope(ScopeType::LoopExpr);
+                if let Err(err) = self.typer.enter_loop(label.clone()) {
+                    self.errors.add_error(err);
+                }
                 self.traverse_body(body);
-                self.typer.set_scope(prev_scope);
+                self.init.exit_loop(ended);
+                self.typer.exit_loop();
             }
             Loop::Error => println!("Error in Loop"),
         }
     }
 
+    // Variables assigned when the condition of a loop ends it, which never
+    // happens for a condition that is always true
+    fn loop_ended(&self, cond: &CondExpr) -> Option<HashSet<i32>> {
+        match cond {
+            CondExpr::Bool(true) => None,
+            _ => self.init.snapshot(),
+        }
+    }
+
     fn traverse_cond_expr(&mut self, cond: &CondExpr) -> Option<BasicType> {
         match cond {
             CondExpr::Bool(_) => {
@@ -743,9 +1652,9 @@ impl Walker {
                 if self.verbose {
                     println!("Condition: {:?} {:?} {:?}", lhs, op, rhs);
                 }
-                let left_type = self.traverse_comp_expr(lhs)?;
-                let right_type = self.traverse_comp_expr(rhs)?;
-                match self.typer.check_condition(left_type, right_type) {
+                let left_type = self.traverse_comp_expr(lhs);
+                let right_type = self.traverse_comp_expr(rhs);
+                match self.typer.check_condition(op, left_type, right_type) {
                     Ok(t) => Some(t),
                     Err(err) => {
                         self.errors.add_error(err);
@@ -757,7 +1666,12 @@ impl Walker {
         }
     }
 
-    fn traverse_comp_expr(&mut self, comp: &CompExpr) -> Option<VarType> {
+    // Type of an expression, the error type if it failed to check
+    fn traverse_comp_expr(&mut self, comp: &CompExpr) -> VarType {
+        self.check_comp_expr(comp).unwrap_or_else(VarType::error)
+    }
+
+    fn check_comp_expr(&mut self, comp: &CompExpr) -> Option<VarType> {
         match comp {
             CompExpr::Value(value) => {
                 if self.verbose {
@@ -769,6 +1683,7 @@ impl Walker {
                 if self.verbose {
                     println!("Variable: {:?}", variable);
                 }
+                self.read_variable(variable);
                 return self.traverse_variable(variable);
             }
             CompExpr::FuncCall(function) => {
@@ -776,23 +1691,37 @@ impl Walker {
                     println!("Function Call");
                 }
                 let func_type = self.traverse_function(function)?;
+                // Void functions can only be called as statements
+                if func_type.0 == BasicType::Void {
+                    let name = match function {
+                        Function::FuncReference(name, ..) => name.as_str(),
+                        _ => "",
+                    };
+                    self.errors.add_error(SemanticError::ImproperUsageError {
+                        id: 29,
+                        message: format!("Function {} returns Void, its value cannot be used", name),
+                        line: 0
+                    });
+                    return None;
+                }
                 return Some(VarType::Primitive(func_type.0));
             }
             CompExpr::UnaryOperation(op, expr) => {
                 if self.verbose {
                     println!("UnaryOperation: {:?}", op);
                 }
-                // From the grammar, unary operators can only operate on variables
-                // and only support two operations: "&" and "*", "&" can only operate
-                // on variables(int, float, char) and "*" can only operate on pointers.
-                let var_type = self.traverse_comp_expr(expr)?;
+                // From the grammar, unary operators only support two operations: "&" and "*".
+                // "&" can only operate on left values and "*" can only operate on pointers.
+                if let UnaryOperator::Ref = op {
+                    self.take_address(expr);
+                }
+                let var_type = self.traverse_comp_expr(expr);
                 return match op {
                     UnaryOperator::Ref => {
-                        match var_type {
-                            VarType::Primitive(BasicType::Int) => Some(VarType::Primitive(BasicType::Pointer(Box::new(BasicType::Int)))),
-                            VarType::Primitive(BasicType::Float) => Some(VarType::Primitive(BasicType::Pointer(Box::new(BasicType::Float)))),
-                            VarType::Primitive(BasicType::Char) => Some(VarType::Primitive(BasicType::Pointer(Box::new(BasicType::Char)))),
-                            VarType::Array((t, _)) => Some(VarType::Primitive(BasicType::Pointer(Box::new(t)))),
+                        match (expr.as_ref(), var_type) {
+                            (_, VarType::Primitive(BasicType::Error)) => Some(VarType::error()),
+                            (CompExpr::Variable(_), VarType::Primitive(t)) => Some(VarType::Primitive(BasicType::Pointer(Box::new(t)))),
+                            (CompExpr::Variable(_), VarType::Array((t, _))) => Some(VarType::Primitive(BasicType::Pointer(Box::new(t)))),
                             _ => {
                                 self.errors.add_error(SemanticError::ImproperUsageError {
                                     id: 11,
@@ -803,19 +1732,7 @@ impl Walker {
                             }
                         }
                     }
-                    UnaryOperator::Deref => {
-                        match var_type {
-                            VarType::Primitive(BasicType::Pointer(t)) => Some(VarType::Primitive(*t)),
-                            _ => {
-                                self.errors.add_error(SemanticError::ImproperUsageError {
-                                    id: 11,
-                                    message: "Invalid Dereference Operation.".to_owned(),
-                                    line: 0
-                                });
-                                None
-                            }
-                        }
-                    }
+                    UnaryOperator::Deref => self.handle_dereference(var_type),
                     _ => None
                 }
             }
@@ -823,9 +1740,21 @@ impl Walker {
                 if self.verbose {
                     println!("BinaryOperation: {:?} {:?} {:?}", lhs, op, rhs);
                 }
-                let left_type = self.traverse_comp_expr(lhs)?;
-                let right_type = self.traverse_comp_expr(rhs)?;
-                match self.typer.check_binary_operations(left_type, right_type) {
+                let left_type = self.traverse_comp_expr(lhs);
+                let right_type = self.traverse_comp_expr(rhs);
+                if left_type.is_error() || right_type.is_error() {
+                    return Some(VarType::error());
+                }
+                let is_pointer = |t: &VarType| matches!(t, VarType::Primitive(BasicType::Pointer(_)));
+                let is_string = |t: &VarType| matches!(t, VarType::Primitive(BasicType::String));
+                let result = if is_pointer(&left_type) || is_pointer(&right_type) {
+                    self.typer.check_pointer_operation(op, left_type, right_type)
+                } else if is_string(&left_type) || is_string(&right_type) {
+                    self.typer.check_string_operation(op, left_type, right_type)
+                } else {
+                    self.typer.check_binary_operations(left_type, right_type)
+                };
+                match result {
                     Ok(t) => Some(t),
                     Err(err) => {
                         self.errors.add_error(err);
@@ -833,6 +1762,35 @@ impl Walker {
                     }
                 }
             }
+            CompExpr::New(ty, count) => {
+                if self.verbose {
+                    println!("New: {:?}, Count: {:?}", ty, count);
+                }
+                let basic_type = self.check_type_defined(ty)?;
+                let count = count.as_ref().map(|count| self.traverse_comp_expr(count));
+                match self.typer.check_allocation(basic_type, count) {
+                    Ok(t) => Some(t),
+                    Err(err) => {
+                        self.errors.add_error(err);
+                        None
+                    }
+                }
+            }
+            CompExpr::SizeOf(ty) => {
+                if self.verbose {
+                    println!("SizeOf: {:?}", ty);
+                }
+                self.check_type_defined(ty)?;
+                Some(VarType::Primitive(BasicType::Int(IntType::INT)))
+            }
+            CompExpr::InitList(_) | CompExpr::Designated(_, _) => {
+                self.errors.add_error(SemanticError::ImproperUsageError {
+                    id: 14,
+                    message: "Initializer lists can only be used in assignments.".to_owned(),
+                    line: 0
+                });
+                None
+            }
             CompExpr::Error | CompExpr::Invalid | CompExpr::MissingRP => {
                 None
             }
@@ -0,0 +1,54 @@
+use crate::tree::{BinaryOperator, CompExpr, Value, Variable};
+
+/// Value of a constant expression, folded at compile time.
+/// The analyser uses it for array sizes, indices and `const` variables,
+/// the IR generator for array types and the initial values of constants.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConstValue {
+    Int(i64),
+    // Floats and doubles alike, folded in double precision
+    Float(f64),
+}
+
+impl CompExpr {
+    /// Fold the expression into a constant, `lookup` resolves the value of a
+    /// `const` variable by name. None if the expression is not a constant,
+    /// overflows or divides by zero.
+    pub fn eval_const(&self, lookup: &dyn Fn(&str) -> Option<ConstValue>) -> Option<ConstValue> {
+        match self {
+            CompExpr::Value(Value::Integer(n)) => Some(ConstValue::Int(*n as i64)),
+            CompExpr::Value(Value::SizedInteger(n, _)) => i64::try_from(*n).ok().map(ConstValue::Int),
+            CompExpr::Value(Value::Char(c)) => Some(ConstValue::Int(*c as i64)),
+            CompExpr::Value(Value::Float(f)) => Some(ConstValue::Float(*f)),
+            CompExpr::Value(Value::Double(d)) => Some(ConstValue::Float(*d)),
+            CompExpr::Variable(Variable::VarReference(name, dims)) if dims.is_empty() => lookup(name),
+            CompExpr::BinaryOperation(lhs, op, rhs) => {
+                match (lhs.eval_const(lookup)?, rhs.eval_const(lookup)?) {
+                    (ConstValue::Int(l), ConstValue::Int(r)) => {
+                        let result = match op {
+                            BinaryOperator::Add => l.checked_add(r),
+                            BinaryOperator::Sub => l.checked_sub(r),
+                            BinaryOperator::Mul => l.checked_mul(r),
+                            BinaryOperator::Div => l.checked_div(r),
+                            BinaryOperator::Mod => l.checked_rem(r),
+                            BinaryOperator::BitwiseAnd => Some(l & r),
+                            BinaryOperator::BitwiseOr => Some(l | r),
+                            BinaryOperator::BitwiseXor => Some(l ^ r),
+                            _ => None,
+                        };
+                        result.map(ConstValue::Int)
+                    }
+                    (ConstValue::Float(l), ConstValue::Float(r)) => match op {
+                        BinaryOperator::Add => Some(ConstValue::Float(l + r)),
+                        BinaryOperator::Sub => Some(ConstValue::Float(l - r)),
+                        BinaryOperator::Mul => Some(ConstValue::Float(l * r)),
+                        BinaryOperator::Div => Some(ConstValue::Float(l / r)),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
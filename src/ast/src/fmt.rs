@@ -14,7 +14,7 @@ impl fmt::Display for ProgramPart {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProgramPart::Statement(statement) => write!(f, "Statement: {}", statement),
-            ProgramPart::Function(function) => write!(f, "Functions: {}", function),
+            ProgramPart::Function(function, _) => write!(f, "Functions: {}", function),
         }
     }
 }
@@ -38,6 +38,7 @@ impl fmt::Display for Variable {
                 ident,
                 value,
                 dims.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", ")),
+            Variable::ConstDeclaration(ident, value, init) => write!(f, "Const Declaration: {} = [{}] with value {}", ident, value, init),
             Variable::FormalParameter(ident, value, dims) => write!(f, "Formal Parameter: {} = [{}] with dimensions [{}]",
                 ident,
                 value,
@@ -47,6 +48,7 @@ impl fmt::Display for Variable {
             Variable::VarAssignment(ident, expr) => {
                 write!(f, "Variable Assignment: {} = {}", ident, expr.iter().map(|e| format!("{}", e)).collect::<Vec<String>>().join(", "))
             },
+            Variable::StructForward(ident) => write!(f, "Struct Forward Declaration: {}", ident),
             Variable::StructDefinition(ident, vars) => write!(f, "Struct Definition: {} with [{}]",
                 ident, 
                 vars.iter().map(|v| format!("{}", v)).collect::<Vec<String>>().join(", ")),
@@ -61,6 +63,7 @@ impl fmt::Display for Variable {
                         _ => format!("{}", v),
                     }
                 }).collect::<Vec<String>>().join(", ")),
+            Variable::Dereference(expr) => write!(f, "(* {})", expr),
             Variable::Error => write!(f, "[VariableError]"),
         }
     }
@@ -69,11 +72,12 @@ impl fmt::Display for Variable {
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Function::FuncReference(ident, input_params) => write!(f, "FuncCall: {}[{}]", 
+            Function::FuncReference(ident, input_params, _) => write!(f, "FuncCall: {}[{}]", 
                 ident, 
                 input_params.iter().map(|v| format!("{}", v)).collect::<Vec<String>>().join(", ")
             ),
             Function::FuncDeclaration(ident, _input_params, _output_param, body) => write!(f, "Function: {}:[{}]", ident,  body),
+            Function::Generic(params, decl) => write!(f, "Generic<{}> {}", params.join(", "), decl),
             Function::Error => write!(f, "[FunctionError]"),
         }
     }
@@ -87,6 +91,13 @@ impl fmt::Display for CompExpr {
             CompExpr::UnaryOperation(op, expr) => write!(f, "({} {})", op, expr),
             CompExpr::BinaryOperation(left, op, right) => write!(f, "({} {} {})", left, op, right),
             CompExpr::FuncCall(func) => write!(f, "{}", func),
+            CompExpr::New(ty, count) => match count {
+                Some(count) => write!(f, "New [{}; {}]", ty, count),
+                None => write!(f, "New {}", ty),
+            },
+            CompExpr::SizeOf(ty) => write!(f, "SizeOf({})", ty),
+            CompExpr::InitList(items) => write!(f, "{{{}}}", items.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", ")),
+            CompExpr::Designated(field, val) => write!(f, ".{} = {}", field, val),
             CompExpr::MissingRP => write!(f, "[CompExprError]"),
             CompExpr::Invalid => write!(f, "[Invalid]"),
             CompExpr::Error => write!(f, "[CompExprError]"),
@@ -110,17 +121,27 @@ impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Integer(i) => write!(f, "{}: u32", i),
+            Value::SizedInteger(i, t) => write!(f, "{}: {}", i, t),
             Value::Float(fl) => write!(f, "{}: f32", fl),
+            Value::Double(d) => write!(f, "{}: f64", d),
             Value::String(s) => write!(f, "{}: String", s),
             Value::Char(c) => write!(f, "{}: char", c),
             Value::Bool(b) => write!(f, "{}: bool", b),
             Value::Struct(s) => write!(f, "Struct({})", s),
             Value::Pointer(p) => write!(f, "Pointer({})", p),
+            Value::TypeParam(t) => write!(f, "TypeParam({})", t),
+            Value::Auto => write!(f, "auto"),
             Value::Null => write!(f, "null")
         }
     }
 }
 
+impl fmt::Display for IntType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits)
+    }
+}
+
 impl fmt::Display for BinaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -204,11 +225,21 @@ impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Expr::If(if_expr, _) => write!(f, "{}", if_expr),
-            Expr::Loop(loop_expr, _) => write!(f, "{}", loop_expr),
-            Expr::Break(_) => write!(f, "Break"),
-            Expr::Continue(_) => write!(f, "Continue"),
+            Expr::Loop(loop_expr, label, _) => match label {
+                Some(label) => write!(f, "Label {}: {}", label, loop_expr),
+                None => write!(f, "{}", loop_expr),
+            },
+            Expr::Break(label, _) => match label {
+                Some(label) => write!(f, "Break {}", label),
+                None => write!(f, "Break"),
+            },
+            Expr::Continue(label, _) => match label {
+                Some(label) => write!(f, "Continue {}", label),
+                None => write!(f, "Continue"),
+            },
             Expr::Body(body, _) => write!(f, "\nNested {}", body),
             Expr::Return(val, _) => write!(f, "Return: {}", val),
+            Expr::Delete(val, _) => write!(f, "Delete: {}", val),
             Expr::FuncCall(func, _) => write!(f, "{}", func),
             Expr::VarManagement(vars, _) => write!(f, "{}", 
                 vars.iter().map(|var| format!("{}", var)).collect::<Vec<String>>().join("; ")),
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use crate::tree::{Function, IntType, Value, Variable};
+
+/// Types bound to the type parameters of a generic function.
+pub type Bindings = HashMap<String, Value>;
+
+impl Value {
+    /// Name of the type inside mangled function names, equal types have
+    /// equal names whatever value the specifier carries.
+    pub fn mangle(&self) -> String {
+        match self {
+            Value::Integer(_) => "int".to_string(),
+            Value::SizedInteger(_, t) => match (t.bits, t.signed) {
+                (64, true) => "long".to_string(),
+                (16, true) => "short".to_string(),
+                (8, false) => "byte".to_string(),
+                _ if *t == IntType::INT => "int".to_string(),
+                (bits, signed) => format!("{}{}", if signed { "i" } else { "u" }, bits),
+            },
+            Value::Float(_) => "float".to_string(),
+            Value::Double(_) => "double".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Char(_) => "char".to_string(),
+            Value::Bool(_) => "bool".to_string(),
+            Value::Struct(name) => format!("struct_{}", name),
+            Value::Pointer(inner) => format!("{}_ptr", inner.mangle()),
+            Value::TypeParam(name) => name.clone(),
+            Value::Auto => "auto".to_string(),
+            Value::Null => "void".to_string(),
+        }
+    }
+
+    /// The type with its type parameters replaced by their bindings.
+    pub fn substitute(&self, bindings: &Bindings) -> Value {
+        match self {
+            Value::TypeParam(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Value::Pointer(inner) => Value::Pointer(Box::new(inner.substitute(bindings))),
+            _ => self.clone(),
+        }
+    }
+
+    /// Bind the type parameters of this parameter type to the matching parts
+    /// of the argument type. Parts that do not line up are left to the usual
+    /// argument checks, a parameter bound to two different types is an error.
+    pub fn bind(&self, arg: &Value, bindings: &mut Bindings) -> Result<(), String> {
+        match (self, arg) {
+            (Value::TypeParam(name), _) => match bindings.get(name) {
+                Some(bound) if bound.mangle() != arg.mangle() => Err(format!(
+                    "Type parameter {} is bound to both {} and {}", name, bound.mangle(), arg.mangle()
+                )),
+                Some(_) => Ok(()),
+                None => {
+                    bindings.insert(name.clone(), arg.clone());
+                    Ok(())
+                }
+            },
+            (Value::Pointer(param), Value::Pointer(arg)) => param.bind(arg, bindings),
+            _ => Ok(()),
+        }
+    }
+
+    /// The type parameter this type is built from, if any.
+    pub fn type_param(&self) -> Option<&String> {
+        match self {
+            Value::TypeParam(t) => Some(t),
+            Value::Pointer(inner) => inner.type_param(),
+            _ => None,
+        }
+    }
+}
+
+impl Function {
+    /// Infer the bindings of a generic function from the element types of
+    /// the arguments of a call.
+    pub fn infer(&self, args: &[Value]) -> Result<Bindings, String> {
+        let mut bindings = Bindings::new();
+        if let Function::Generic(types, decl) = self {
+            if let Function::FuncDeclaration(name, params, _, _) = decl.as_ref() {
+                for (param, arg) in params.iter().zip(args) {
+                    if let Variable::FormalParameter(_, ty, _) = param {
+                        ty.bind(arg, &mut bindings)?;
+                    }
+                }
+                if let Some(t) = types.iter().find(|t| !bindings.contains_key(*t)) {
+                    return Err(format!("Cannot infer type parameter {} of {}", t, name));
+                }
+            }
+        }
+        Ok(bindings)
+    }
+
+    /// Name of the instantiation of a generic function, e.g. `max.int`.
+    pub fn mangled_name(&self, bindings: &Bindings) -> String {
+        match self {
+            Function::Generic(types, decl) => match decl.as_ref() {
+                Function::FuncDeclaration(name, _, _, _) => {
+                    let mut mangled = name.to_string();
+                    for t in types {
+                        mangled.push('.');
+                        mangled.push_str(&bindings.get(t).map(Value::mangle).unwrap_or_else(|| t.clone()));
+                    }
+                    mangled
+                }
+                _ => String::new(),
+            },
+            _ => String::new(),
+        }
+    }
+
+    /// The declaration of a generic function with its type parameters
+    /// replaced, named after the bindings. Type parameters only appear in
+    /// the signature, so the body is copied as it is.
+    pub fn instantiate(&self, bindings: &Bindings) -> Function {
+        match self {
+            Function::Generic(_, decl) => match decl.as_ref() {
+                Function::FuncDeclaration(_, params, ret, body) => {
+                    let params = params.iter().map(|param| match param {
+                        Variable::FormalParameter(name, ty, dims) => Variable::FormalParameter(
+                            name.clone(),
+                            Box::new(ty.substitute(bindings)),
+                            dims.clone()
+                        ),
+                        _ => param.clone(),
+                    }).collect();
+                    Function::FuncDeclaration(
+                        Box::new(self.mangled_name(bindings)),
+                        params,
+                        Box::new(ret.substitute(bindings)),
+                        body.clone()
+                    )
+                }
+                _ => Function::Error,
+            },
+            _ => self.clone(),
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod tree;
-pub mod fmt;
\ No newline at end of file
+pub mod fmt;pub mod eval;
+pub mod generic;
\ No newline at end of file
@@ -10,7 +10,7 @@ pub enum Program{
 #[derive(Clone, Debug, PartialEq)]
 pub enum ProgramPart {
     Statement(Box<Statement>),
-    Function(Box<Function>),
+    Function(Box<Function>, Span),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -33,24 +33,35 @@ pub enum Variable {
     // (identifier, dimensions)
     VarReference(Box<String>, Box<Vec<CompExpr>>), // varname, offsets
     VarDeclaration(Box<String>, Box<Value>, Box<Vec<CompExpr>>), // varname, type, offsets
+    ConstDeclaration(Box<String>, Box<Value>, Box<CompExpr>), // varname, type, constant value
     
     // Struct definition and declaration
     StructDefinition(Box<String>, Box<Vec<Variable>>),
+    // Forward declaration of a struct, completed by a later definition
+    StructForward(Box<String>),
     StructDeclaration(Box<String>, Box<String>, Box<Vec<CompExpr>>),
     // Struct Reference accepts a recursive call of VarReference
     // The first represents the struct variable name and the others are member fields.
     StructReference(Box<Vec<Variable>>),
     
     // Function Parameter
+    // (identifier, type, dimensions), an omitted outermost length is 0
     FormalParameter(Box<String>, Box<Value>, Box<Vec<usize>>),
+
+    // Dereference of a pointer expression, used as left value
+    // and as the base of `->` member access.
+    Dereference(Box<CompExpr>),
     Error
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Function {
     // (identifier, input_params, output_params, body)
-    FuncReference(Box<String>, Vec<Box<CompExpr>>),
+    // A call spans from its name to the closing parenthesis
+    FuncReference(Box<String>, Vec<Box<CompExpr>>, Span),
     FuncDeclaration(Box<String>, Vec<Variable>, Box<Value>, Body),
+    // (type parameters, declaration), instantiated for the argument types of each call
+    Generic(Vec<String>, Box<Function>),
     Error
 }
 
@@ -65,6 +76,15 @@ pub enum CompExpr {
     UnaryOperation(UnaryOperator, Box<CompExpr>),
     // Binary Operator can operate on all types of Values.
     BinaryOperation(Box<CompExpr>, BinaryOperator, Box<CompExpr>),
+    // Heap allocation: (type, optional element count)
+    New(Box<Value>, Option<Box<CompExpr>>),
+    // Size of a type in bytes
+    SizeOf(Box<Value>),
+    // Brace initializer of an array or a struct, items may be
+    // nested lists or designated fields, the rest is zero filled
+    InitList(Vec<CompExpr>),
+    // `.field = value` inside an initializer list
+    Designated(Box<String>, Box<CompExpr>),
     MissingRP,
     Invalid,
     Error
@@ -82,15 +102,78 @@ pub enum CondExpr {
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Integer(u32),
-    Float(f32),
+    // Integers other than `int`: long, short, byte, unsigned and suffixed literals
+    SizedInteger(u64, IntType),
+    // Literals keep double precision, the type is still `float`
+    Float(f64),
+    Double(f64),
     String(String),
     Char(char),
     Bool(bool),
     Struct(String),
     Pointer(Box<Value>),
+    // Type parameter of a generic function, replaced when it is instantiated
+    TypeParam(String),
+    // Type of an `auto` declaration, inferred from its initializer
+    Auto,
     Null
 }
 
+impl Value {
+    // An unsuffixed integer literal, `int` unless its value needs a `long`
+    pub fn int_literal(n: u64) -> Value {
+        match i32::try_from(n) {
+            Ok(_) => Value::Integer(n as u32),
+            Err(_) => Value::SizedInteger(n, IntType::LONG),
+        }
+    }
+}
+
+// Width in bits and signedness of an integer type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IntType {
+    pub bits: u32,
+    pub signed: bool,
+}
+
+impl IntType {
+    // The type of `int` and unsuffixed literals
+    pub const INT: IntType = IntType { bits: 32, signed: true };
+    // The type of `long` and unsuffixed literals too large for an `int`
+    pub const LONG: IntType = IntType { bits: 64, signed: true };
+
+    // Name of the type in the source, e.g. `unsigned short`
+    pub fn name(self) -> String {
+        match (self.bits, self.signed) {
+            (8, false) => "byte".to_string(),
+            (16, true) => "short".to_string(),
+            (32, true) => "int".to_string(),
+            (64, true) => "long".to_string(),
+            (16 | 32 | 64, false) => format!("unsigned {}", IntType { signed: true, ..self }.name()),
+            _ => self.to_string(),
+        }
+    }
+
+    // The value held after storing n, which wraps around when out of range
+    pub fn wrap(self, n: i128) -> i128 {
+        let raw = (n as u128) & (u128::MAX >> (128 - self.bits));
+        match self.signed && raw >> (self.bits - 1) == 1 {
+            true => raw as i128 - (1i128 << self.bits),
+            false => raw as i128,
+        }
+    }
+
+    // Type of a binary operation on two integers like the C usual arithmetic
+    // conversions: the wider type wins, unsigned wins between equal widths
+    pub fn common(self, other: IntType) -> IntType {
+        match self.bits.cmp(&other.bits) {
+            std::cmp::Ordering::Greater => self,
+            std::cmp::Ordering::Less => other,
+            std::cmp::Ordering::Equal => IntType { bits: self.bits, signed: self.signed && other.signed },
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum BinaryOperator {
     Add, // +
@@ -150,26 +233,47 @@ pub enum Body {
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr{
     If(If, Span),
-    Loop(Loop, Span),
+    // (loop, optional label)
+    Loop(Loop, Option<String>, Span),
     VarManagement(Vec<Variable>, Span),
     FuncCall(Function, Span),
     Body(Body, Span),
-    Break(Span),
-    Continue(Span),
+    // Optional label of the targeted loop
+    Break(Option<String>, Span),
+    Continue(Option<String>, Span),
     Return(CompExpr, Span),
+    // Release of heap memory obtained by `new`
+    Delete(CompExpr, Span),
     Error
 }
 
+impl Expr {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Expr::If(_, span) | Expr::Loop(_, _, span) | Expr::VarManagement(_, span)
+            | Expr::FuncCall(_, span) | Expr::Body(_, span) | Expr::Break(_, span)
+            | Expr::Continue(_, span) | Expr::Return(_, span) | Expr::Delete(_, span) => Some(span),
+            Expr::Error => None
+        }
+    }
+}
+
 impl Variable {
     pub fn get_name(&self) -> String {
         match self {
             Variable::VarAssignment(var, _) => var.deref().get_name(),
             Variable::VarReference(name, _) => name.deref().clone(),
             Variable::VarDeclaration(name, _, _) => name.deref().clone(),
+            Variable::ConstDeclaration(name, _, _) => name.deref().clone(),
             Variable::StructDefinition(name, _) => name.deref().clone(),
+            Variable::StructForward(name) => name.deref().clone(),
             Variable::StructDeclaration(name, _, _) => name.deref().clone(),
             Variable::StructReference(vars) => vars.deref().iter().map(|v| v.get_name()).collect::<Vec<String>>().join("."),
             Variable::FormalParameter(name, _, _) => name.deref().clone(),
+            Variable::Dereference(expr) => match expr.deref() {
+                CompExpr::Variable(var) => var.get_name(),
+                _ => "deref".to_string()
+            },
             Variable::Error => "Error".to_string()
         }
     }
@@ -1,11 +1,14 @@
 use inkwell as llvm;
 use std::collections::HashMap;
-use inkwell::AddressSpace;
+use inkwell::{AddressSpace, FloatPredicate, IntPredicate};
+use inkwell::attributes::Attribute;
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::Linkage;
 use inkwell::targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine};
-use inkwell::types::{BasicTypeEnum, IntType};
-use inkwell::values::{BasicMetadataValueEnum, PointerValue};
+use inkwell::types::{AnyType, ArrayType, BasicType, BasicTypeEnum, IntType, StructType};
+use spl_ast::tree;
+use spl_ast::eval::ConstValue;
+use inkwell::values::{BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::basic_block::BasicBlock;
 
 
@@ -13,17 +16,41 @@ use inkwell::basic_block::BasicBlock;
 /// 'ctx is the lifetime of the LLVM context, 'ast is the lifetime of the input AST
 /// `printf` and `scanf` store the function values, lazy initialized
 /// scope is a stack of hashmaps, each hashmap stores the variables in the current scope
+/// together with their source-level types, which are needed to recover pointee types
+/// globals stores the source-level types of the global variables
+/// consts stores the folded values of the const variables, the first map holds the
+/// global ones and the others are the local ones of the matching `scope`
+/// ret_types stores the source-level return types of the declared functions by their symbol
 /// loops is a stack of Loop structs, each struct stores the loop_start and after_loop basic blocks
+/// and the optional label of the loop
+/// ret_ptr is the `sret` pointer of the function being emitted if it returns a struct
 /// struct_fields is a hashmap of hashmaps, the first hashmap stores the struct name and the second
-/// hashmap stores the field names and their indices
+/// hashmap stores the field names, their indices and their source-level types
+/// global_ctor is the module constructor evaluating non-constant global initializers, lazy initialized
+/// strings stores the string variables of each `scope` with their number of slots, released
+/// when the scope ends, and the string fields of its structs with the stride between them.
+/// str_mark is the mark of the temporary string pool taken when the current function is
+/// entered, lazy initialized, and str_temps tells whether the statement being emitted
+/// created temporary strings, see the runtime library for the memory model
+/// functions stores the function declarations of the program by name, for the calls coming
+/// before them
 pub(crate) struct Azuki<'ast, 'ctx> {
     pub context: &'ctx llvm::context::Context,
     pub builder: llvm::builder::Builder<'ctx>,
     pub module: llvm::module::Module<'ctx>,
 
-    pub scope: Vec<HashMap<&'ast str, (PointerValue<'ctx>, BasicTypeEnum<'ctx>)>>,
-    pub loops: Vec<Loop<'ctx>>,
-    pub struct_fields: HashMap<&'ast str, HashMap<&'ast str, usize>>,
+    pub scope: Vec<HashMap<&'ast str, (PointerValue<'ctx>, BasicTypeEnum<'ctx>, tree::Value)>>,
+    pub globals: HashMap<&'ast str, tree::Value>,
+    pub consts: Vec<HashMap<&'ast str, ConstValue>>,
+    pub ret_types: HashMap<String, tree::Value>,
+    pub ret_ptr: Option<PointerValue<'ctx>>,
+    pub loops: Vec<Loop<'ast, 'ctx>>,
+    pub struct_fields: HashMap<&'ast str, HashMap<&'ast str, (usize, tree::Value)>>,
+    global_ctor: Option<FunctionValue<'ctx>>,
+    pub strings: Vec<Vec<(PointerValue<'ctx>, u64, Option<IntValue<'ctx>>)>>,
+    pub str_mark: Option<IntValue<'ctx>>,
+    pub str_temps: bool,
+    pub functions: HashMap<&'ast str, &'ast tree::Function>,
     printf: Option<llvm::values::FunctionValue<'ctx>>,
     scanf: Option<llvm::values::FunctionValue<'ctx>>,
 }
@@ -35,8 +62,17 @@ impl<'ast, 'ctx> Azuki<'ast, 'ctx> {
             builder: context.create_builder(),
             module: context.create_module(source),
             scope: Vec::new(),
+            globals: HashMap::new(),
+            consts: vec![HashMap::new()],
+            ret_types: HashMap::new(),
+            ret_ptr: None,
             loops: Vec::new(),
             struct_fields: HashMap::new(),
+            global_ctor: None,
+            strings: Vec::new(),
+            str_mark: None,
+            str_temps: false,
+            functions: HashMap::new(),
             printf: None,
             scanf: None,
         }
@@ -80,6 +116,198 @@ impl<'ast, 'ctx> Azuki<'ast, 'ctx> {
         pointer_value.unwrap()
     }
 
+    // Declaration of a function of the runtime library, added on first use
+    pub(crate) fn runtime_fn(&self, name: &str) -> FunctionValue<'ctx> {
+        if let Some(func) = self.module.get_function(name) {
+            return func;
+        }
+        let ptr = self.context.ptr_type(AddressSpace::default());
+        let (i32_type, i64_type) = (self.context.i32_type(), self.context.i64_type());
+        let fn_type = match name {
+            "spl_str_new" | "spl_str_cstr" | "spl_str_keep" => ptr.fn_type(&[ptr.into()], false),
+            "spl_str_concat" => ptr.fn_type(&[ptr.into(), ptr.into()], false),
+            "spl_str_cmp" => i32_type.fn_type(&[ptr.into(), ptr.into()], false),
+            "spl_str_len" | "spl_str_to_int" => i32_type.fn_type(&[ptr.into()], false),
+            "spl_str_at" => self.context.i8_type().fn_type(&[ptr.into(), i32_type.into()], false),
+            "spl_str_from_int" => ptr.fn_type(&[i32_type.into()], false),
+            "spl_str_from_double" => ptr.fn_type(&[self.context.f64_type().into()], false),
+            "spl_str_to_double" => self.context.f64_type().fn_type(&[ptr.into()], false),
+            "spl_str_assign" => self.context.void_type().fn_type(&[ptr.into(), ptr.into()], false),
+            "spl_str_release" => self.context.void_type().fn_type(&[ptr.into(), i64_type.into()], false),
+            "spl_str_retain_fields" | "spl_str_release_fields" | "spl_str_keep_fields" =>
+                self.context.void_type().fn_type(&[ptr.into(), i64_type.into(), i64_type.into()], false),
+            "spl_str_mark" => i64_type.fn_type(&[], false),
+            "spl_str_drain" => self.context.void_type().fn_type(&[i64_type.into()], false),
+            _ => panic!("Unknown runtime function {}", name),
+        };
+        self.module.add_function(name, fn_type, Some(Linkage::External))
+    }
+
+    pub(crate) fn emit_runtime_call(&mut self, name: &str, args: &[BasicMetadataValueEnum<'ctx>]) -> Option<BasicValueEnum<'ctx>> {
+        let func = self.runtime_fn(name);
+        let call = self.builder.build_call(func, args, "").expect("Error in emit_runtime_call");
+        let result = call.try_as_basic_value().left();
+        if result.is_some_and(|v| v.is_pointer_value()) && name != "spl_str_cstr" {
+            self.str_temps = true;
+        }
+        result
+    }
+
+    // Store a string into a variable, which owns a reference to it
+    pub(crate) fn emit_string_store(&mut self, slot: PointerValue<'ctx>, value: BasicValueEnum<'ctx>) {
+        self.emit_runtime_call("spl_str_assign", &[slot.into(), value.into()]);
+    }
+
+    // Register string slots of the innermost scope, they start out empty
+    pub(crate) fn add_string_slots(&mut self, slots: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, count: u64) {
+        self.builder.build_store(slots, ty.const_zero()).expect("Store failed");
+        self.strings.last_mut().unwrap().push((slots, count, None));
+    }
+
+    // Whether a value of the type holds strings, in its fields or theirs
+    pub(crate) fn holds_strings(&self, ty: &tree::Value) -> bool {
+        match ty {
+            tree::Value::String(_) => true,
+            tree::Value::Struct(name) => self.struct_fields.get(name.as_str())
+                .is_some_and(|fields| fields.values().any(|(_, t)| self.holds_strings(t))),
+            _ => false,
+        }
+    }
+
+    // Runs of string slots in memory of the type, holding values of `value`,
+    // as (first slot, number of slots, stride). A field of every element of
+    // an array of structs makes up a run, arrays within them are unrolled.
+    pub(crate) fn string_fields(&self, ptr: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, value: &tree::Value) -> Vec<(PointerValue<'ctx>, u64, IntValue<'ctx>)> {
+        if !self.holds_strings(value) {
+            return Vec::new();
+        }
+        let i32_type = self.context.i32_type();
+        let gep = |indices: &[u32]| {
+            let indices = indices.iter().map(|i| i32_type.const_int(*i as u64, false)).collect::<Vec<_>>();
+            unsafe { self.builder.build_in_bounds_gep(ty, ptr, &indices, "field").unwrap() }
+        };
+        match (ty, value) {
+            (_, tree::Value::String(_)) => {
+                let (mut count, mut elem_ty) = (1, ty);
+                while let BasicTypeEnum::ArrayType(t) = elem_ty {
+                    count *= t.len() as u64;
+                    elem_ty = t.get_element_type();
+                }
+                vec![(ptr, count, self.context.ptr_type(AddressSpace::default()).size_of())]
+            }
+            (BasicTypeEnum::StructType(t), tree::Value::Struct(name)) => {
+                let mut fields = self.struct_fields[name.as_str()].values().collect::<Vec<_>>();
+                fields.sort_by_key(|(i, _)| *i);
+                fields.into_iter()
+                    .filter(|(_, field)| self.holds_strings(field))
+                    .flat_map(|(i, field)| {
+                        let field_ty = t.get_field_type_at_index(*i as u32).unwrap();
+                        self.string_fields(gep(&[0, *i as u32]), field_ty, field)
+                    })
+                    .collect()
+            }
+            (BasicTypeEnum::ArrayType(t), tree::Value::Struct(_)) => {
+                let elem_ty = t.get_element_type();
+                let len = t.len() as u64;
+                self.string_fields(gep(&[0, 0]), elem_ty, value).into_iter()
+                    .flat_map(|(first, count, stride)| match (count, elem_ty) {
+                        // One field of a struct, the same one of each element follows
+                        (1, BasicTypeEnum::StructType(_)) => vec![(first, len, elem_ty.size_of().unwrap())],
+                        _ => (0..len).map(|i| {
+                            let offset = self.context.i64_type().const_int(i, false);
+                            let elem = unsafe { self.builder.build_in_bounds_gep(elem_ty, first, &[offset], "elem").unwrap() };
+                            (elem, count, stride)
+                        }).collect(),
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Hand the string fields of memory of the type to a runtime function
+    // taking runs of slots, see `string_fields`
+    pub(crate) fn emit_string_fields(&mut self, runtime: &str, ptr: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, value: &tree::Value) {
+        for (first, count, stride) in self.string_fields(ptr, ty, value) {
+            let count = self.context.i64_type().const_int(count, false);
+            self.emit_runtime_call(runtime, &[first.into(), count.into(), stride.into()]);
+        }
+    }
+
+    // Register the string fields of a struct of the innermost scope, the
+    // struct owns a reference to each of them
+    pub(crate) fn add_string_fields(&mut self, ptr: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, value: &tree::Value) {
+        let fields = self.string_fields(ptr, ty, value);
+        self.strings.last_mut().unwrap().extend(fields.into_iter().map(|(first, count, stride)| (first, count, Some(stride))));
+    }
+
+    // Release the string variables of the scopes from `depth` on, innermost first
+    pub(crate) fn release_strings(&mut self, depth: usize) {
+        let slots = self.strings[depth..].iter().rev().flatten().copied().collect::<Vec<_>>();
+        for (ptr, count, stride) in slots {
+            let count = self.context.i64_type().const_int(count, false);
+            match stride {
+                Some(stride) => self.emit_runtime_call("spl_str_release_fields", &[ptr.into(), count.into(), stride.into()]),
+                None => self.emit_runtime_call("spl_str_release", &[ptr.into(), count.into()]),
+            };
+        }
+    }
+
+    // Release the temporary strings of the statement just emitted
+    pub(crate) fn drain_strings(&mut self) {
+        if !self.str_temps || !self.no_terminator() {
+            return;
+        }
+        let mark = match self.str_mark {
+            Some(mark) => mark,
+            None => {
+                // Taken at the start of the function, which dominates every statement
+                let func = self.builder.get_insert_block().and_then(|bb| bb.get_parent()).unwrap();
+                let entry = func.get_first_basic_block().unwrap();
+                let builder = self.context.create_builder();
+                match entry.get_first_instruction() {
+                    Some(inst) => builder.position_before(&inst),
+                    None => builder.position_at_end(entry),
+                }
+                let call = builder.build_call(self.runtime_fn("spl_str_mark"), &[], "mark").unwrap();
+                *self.str_mark.insert(call.try_as_basic_value().left().unwrap().into_int_value())
+            }
+        };
+        self.emit_runtime_call("spl_str_drain", &[mark.into()]);
+        self.str_temps = false;
+    }
+
+    // Move the builder to the end of the module constructor, globals are
+    // initialized there in declaration order before `main` runs
+    pub(crate) fn enter_global_ctor(&mut self) {
+        let ctor = *self.global_ctor.get_or_insert_with(|| {
+            let fn_type = self.context.void_type().fn_type(&[], false);
+            let ctor = self.module.add_function("__spl_global_init", fn_type, Some(Linkage::Internal));
+            self.context.append_basic_block(ctor, "entry");
+            ctor
+        });
+        self.builder.position_at_end(ctor.get_last_basic_block().unwrap());
+    }
+
+    // Close the module constructor and register it in `llvm.global_ctors`
+    pub(crate) fn finish_global_ctor(&mut self) {
+        let Some(ctor) = self.global_ctor else { return };
+        self.builder.position_at_end(ctor.get_last_basic_block().unwrap());
+        self.builder.build_return(None).expect("Error in finish_global_ctor");
+
+        let i32_type = self.context.i32_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let entry_type = self.context.struct_type(&[i32_type.into(), ptr_type.into(), ptr_type.into()], false);
+        let entry = entry_type.const_named_struct(&[
+            i32_type.const_int(65535, false).as_basic_value_enum(),
+            ctor.as_global_value().as_pointer_value().as_basic_value_enum(),
+            ptr_type.const_null().as_basic_value_enum(),
+        ]);
+        let ctors = self.module.add_global(entry_type.array_type(1), None, "llvm.global_ctors");
+        ctors.set_linkage(Linkage::Appending);
+        ctors.set_initializer(&entry_type.const_array(&[entry]));
+    }
+
     // Generate Assembly on the fly
     pub(crate) fn gen_code(&mut self) -> MemoryBuffer {
         Target::initialize_all(&InitializationConfig::default());
@@ -102,7 +330,7 @@ impl<'ast, 'ctx> Azuki<'ast, 'ctx> {
     // Get variable from all scopes
     pub(crate) fn get_var(&self, name: &str) -> Option<(PointerValue<'ctx>, BasicTypeEnum<'ctx>)> {
         for scope in self.scope.iter().rev() {
-            if let Some((ptr, ty)) = scope.get(name) {
+            if let Some((ptr, ty, _)) = scope.get(name) {
                 return Some((*ptr, *ty));
             }
         }
@@ -114,6 +342,182 @@ impl<'ast, 'ctx> Azuki<'ast, 'ctx> {
         None
     }
 
+    pub(crate) fn enter_scope(&mut self) {
+        self.scope.push(HashMap::new());
+        self.consts.push(HashMap::new());
+        self.strings.push(Vec::new());
+    }
+
+    pub(crate) fn exit_scope(&mut self) {
+        self.scope.pop();
+        self.consts.pop();
+        self.strings.pop();
+    }
+
+    // Folded value of a const variable, None if the innermost
+    // variable with this name is not a constant
+    pub(crate) fn get_const(&self, name: &str) -> Option<ConstValue> {
+        for (vars, consts) in self.scope.iter().zip(self.consts[1..].iter()).rev() {
+            if let Some(value) = consts.get(name) {
+                return Some(*value);
+            }
+            if vars.contains_key(name) {
+                return None;
+            }
+        }
+        self.consts[0].get(name).copied()
+    }
+
+    // LLVM constant of a folded value with the given source-level type
+    pub(crate) fn const_value(&self, value: ConstValue, ty: &tree::Value) -> BasicValueEnum<'ctx> {
+        match (value, self.get_llvm_type(ty).expect("Constants cannot be void")) {
+            (ConstValue::Int(n), BasicTypeEnum::IntType(t)) => t.const_int(n as u64, true).as_basic_value_enum(),
+            (ConstValue::Float(f), BasicTypeEnum::FloatType(t)) => t.const_float(f).as_basic_value_enum(),
+            _ => panic!("Error in const_value"),
+        }
+    }
+
+    // Get the source-level type of a variable from all scopes
+    pub(crate) fn get_var_type(&self, name: &str) -> Option<tree::Value> {
+        for scope in self.scope.iter().rev() {
+            if let Some((_, _, ty)) = scope.get(name) {
+                return Some(ty.clone());
+            }
+        }
+        self.globals.get(name).cloned()
+    }
+
+    // Map a source-level type to its LLVM type, pointers are opaque
+    pub(crate) fn get_llvm_type(&self, ty: &tree::Value) -> Option<BasicTypeEnum<'ctx>> {
+        match ty {
+            tree::Value::Integer(_) => Some(self.context.i32_type().as_basic_type_enum()),
+            tree::Value::SizedInteger(_, t) => Some(self.context.custom_width_int_type(t.bits).as_basic_type_enum()),
+            tree::Value::Float(_) => Some(self.context.f32_type().as_basic_type_enum()),
+            tree::Value::Double(_) => Some(self.context.f64_type().as_basic_type_enum()),
+            tree::Value::Char(_) => Some(self.context.i8_type().as_basic_type_enum()),
+            tree::Value::Bool(_) => Some(self.context.bool_type().as_basic_type_enum()),
+            tree::Value::String(_) | tree::Value::Pointer(_) =>
+                Some(self.context.ptr_type(AddressSpace::default()).as_basic_type_enum()),
+            tree::Value::Struct(name) => self.module.get_struct_type(name).map(|t| t.as_basic_type_enum()),
+            // Replaced before anything is emitted for a generic function,
+            // and by the inferred type for an `auto` declaration
+            tree::Value::TypeParam(_) | tree::Value::Auto | tree::Value::Null => None,
+        }
+    }
+
+    // Convert an integer to another width, extended by the signedness of its
+    // source type, and a float to double or back (fpext/fptrunc). Constants
+    // are folded so they stay usable as initializers
+    pub(crate) fn emit_cast(&self, value: BasicValueEnum<'ctx>, ty: BasicTypeEnum<'ctx>, signed: bool) -> BasicValueEnum<'ctx> {
+        match (value, ty) {
+            (BasicValueEnum::IntValue(v), BasicTypeEnum::IntType(t)) if v.get_type() != t => {
+                if v.is_const() {
+                    v.const_cast(t, signed).as_basic_value_enum()
+                } else {
+                    self.builder.build_int_cast_sign_flag(v, t, signed, "casttmp").unwrap().as_basic_value_enum()
+                }
+            }
+            (BasicValueEnum::FloatValue(v), BasicTypeEnum::FloatType(t)) if v.get_type() != t => {
+                if v.is_const() {
+                    v.const_cast(t).as_basic_value_enum()
+                } else {
+                    self.builder.build_float_cast(v, t, "casttmp").unwrap().as_basic_value_enum()
+                }
+            }
+            _ => value,
+        }
+    }
+
+    // Structs are passed to and returned from functions in memory, as the C ABI does
+    // for aggregates it does not split into registers: parameters are `byval` pointers
+    // and results are written through a leading `sret` pointer
+    pub(crate) fn type_attribute(&self, name: &str, ty: StructType<'ctx>) -> Attribute {
+        let kind = Attribute::get_named_enum_kind_id(name);
+        self.context.create_type_attribute(kind, ty.as_any_type_enum())
+    }
+
+    // Whole-struct assignment copies the memory of the struct, the copy takes a
+    // reference of its own to each string field. Unless `dest` is fresh memory,
+    // the strings it held are given up, after the new ones are taken in case
+    // they are the same.
+    pub(crate) fn emit_struct_copy(&mut self, dest: PointerValue<'ctx>, src: PointerValue<'ctx>, ty: StructType<'ctx>, fresh: bool) {
+        let value = tree::Value::Struct(ty.get_name().unwrap().to_str().unwrap().to_string());
+        self.emit_copy(dest, src, ty.into(), &value, fresh);
+    }
+
+    // Whole-array assignment copies the memory of the array the same way,
+    // `value` is the type of its elements
+    pub(crate) fn emit_array_copy(&mut self, dest: PointerValue<'ctx>, src: PointerValue<'ctx>, ty: ArrayType<'ctx>, value: &tree::Value) {
+        self.emit_copy(dest, src, ty.into(), value, false);
+    }
+
+    fn emit_copy(&mut self, dest: PointerValue<'ctx>, src: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, value: &tree::Value, fresh: bool) {
+        self.emit_string_fields("spl_str_retain_fields", src, ty, value);
+        if !fresh {
+            self.emit_string_fields("spl_str_release_fields", dest, ty, value);
+        }
+        let size = ty.size_of().expect("Type is incomplete");
+        self.builder.build_memcpy(dest, 1, src, 1, size).expect("Error in emit_copy");
+    }
+
+    // Equality of two values, structs and arrays are compared element by element
+    // and strings by their contents
+    pub(crate) fn emit_value_eq(&mut self, lhs: BasicValueEnum<'ctx>, rhs: BasicValueEnum<'ctx>) -> IntValue<'ctx> {
+        let ty = match lhs {
+            BasicValueEnum::StructValue(v) => v.get_type().get_name()
+                .map_or(tree::Value::Null, |name| tree::Value::Struct(name.to_str().unwrap().to_string())),
+            _ => tree::Value::Null,
+        };
+        self.emit_typed_eq(lhs, rhs, &ty)
+    }
+
+    // Equality of two values of the source-level type `ty`, which tells
+    // strings apart from other pointers
+    fn emit_typed_eq(&mut self, lhs: BasicValueEnum<'ctx>, rhs: BasicValueEnum<'ctx>, ty: &tree::Value) -> IntValue<'ctx> {
+        let fields = match (lhs, rhs) {
+            (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) =>
+                return self.builder.build_int_compare(IntPredicate::EQ, l, r, "eqtmp").unwrap(),
+            (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) =>
+                return self.builder.build_float_compare(FloatPredicate::OEQ, l, r, "eqtmp").unwrap(),
+            (BasicValueEnum::PointerValue(l), BasicValueEnum::PointerValue(r)) if matches!(ty, tree::Value::String(_)) => {
+                let cmp = self.emit_runtime_call("spl_str_cmp", &[l.into(), r.into()]).unwrap().into_int_value();
+                return self.builder.build_int_compare(IntPredicate::EQ, cmp, self.context.i32_type().const_zero(), "eqtmp").unwrap();
+            }
+            (BasicValueEnum::PointerValue(l), BasicValueEnum::PointerValue(r)) =>
+                return self.builder.build_int_compare(IntPredicate::EQ, l, r, "eqtmp").unwrap(),
+            (BasicValueEnum::StructValue(l), BasicValueEnum::StructValue(r)) => {
+                let mut types = match ty {
+                    tree::Value::Struct(name) => self.struct_fields[name.as_str()].values().cloned().collect::<Vec<_>>(),
+                    _ => Vec::new(),
+                };
+                types.sort_by_key(|(i, _)| *i);
+                (0..l.get_type().count_fields())
+                    .map(|i| (
+                        self.builder.build_extract_value(l, i, "lfield").unwrap(),
+                        self.builder.build_extract_value(r, i, "rfield").unwrap(),
+                        types.get(i as usize).map_or(tree::Value::Null, |(_, t)| t.clone()),
+                    ))
+                    .collect::<Vec<_>>()
+            }
+            (BasicValueEnum::ArrayValue(l), BasicValueEnum::ArrayValue(r)) => (0..l.get_type().len())
+                .map(|i| (self.builder.build_extract_value(l, i, "lelem").unwrap(), self.builder.build_extract_value(r, i, "relem").unwrap(), ty.clone()))
+                .collect::<Vec<_>>(),
+            _ => panic!("Error in emit_value_eq"),
+        };
+        fields.into_iter().fold(self.context.bool_type().const_int(1, false), |acc, (l, r, ty)| {
+            let eq = self.emit_typed_eq(l, r, &ty);
+            self.builder.build_and(acc, eq, "eqtmp").unwrap()
+        })
+    }
+
+    // Find the loop targeted by break/continue, the innermost one if no label given
+    pub(crate) fn get_loop(&self, label: Option<&str>) -> Option<&Loop<'ast, 'ctx>> {
+        match label {
+            Some(label) => self.loops.iter().rev().find(|l| l.label == Some(label)),
+            None => self.loops.last(),
+        }
+    }
+
     pub(crate) fn no_terminator(&self) -> bool {
         let block = self.builder.get_insert_block();
         let terminator = block.unwrap().get_terminator();
@@ -121,9 +525,14 @@ impl<'ast, 'ctx> Azuki<'ast, 'ctx> {
     }
 }
 
-pub struct Loop<'ctx> {
+pub struct Loop<'ast, 'ctx> {
     /// Saves the loop_start basic block (for `continue`)
     pub loop_head: BasicBlock<'ctx>,
     /// Saves the after_loop basic block (for `break`)
     pub after_loop: BasicBlock<'ctx>,
+    /// Saves the label of the loop (for `break label`)
+    pub label: Option<&'ast str>,
+    /// Saves the number of scopes outside the loop, the string variables
+    /// of the others are released when jumping out of an iteration
+    pub depth: usize,
 }
@@ -1,13 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use inkwell::AddressSpace;
-use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
+use inkwell::attributes::AttributeLoc;
+use inkwell::types::{ArrayType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
 use inkwell::values::*;
 use crate::azuki::Loop;
 use spl_ast::tree;
+use spl_ast::eval::ConstValue;
 use crate::azuki::Azuki;
 
 
+/// Builtin functions on strings, their runtime library functions and return types
+const BUILTINS: [(&str, &str, tree::Value); 5] = [
+    ("len", "spl_str_len", tree::Value::Integer(0)),
+    ("stoi", "spl_str_to_int", tree::Value::Integer(0)),
+    ("stof", "spl_str_to_double", tree::Value::Double(0.0)),
+    ("itos", "spl_str_from_int", tree::Value::String(String::new())),
+    ("ftos", "spl_str_from_double", tree::Value::String(String::new())),
+];
+
 /// Emit trait is used for AST nodes to emit LLVM IR recursively.
 /// LLVM defines a set of types: Struct, Array, Function, Pointer, Int, Float...
 /// The concrete type can be cast to BasicTypeEnum...
@@ -24,7 +35,25 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Program {
     {
         match self {
             // Top of all, list of global definitions and functions
-            tree::Program::Program(parts) => { parts.iter().for_each(|part| part.emit(emitter)); }
+            tree::Program::Program(parts) => {
+                for (name, _, ret_ty) in BUILTINS.iter() {
+                    emitter.ret_types.insert(name.to_string(), ret_ty.clone());
+                }
+                // Calls may come before the function, e.g. those of an instance
+                // of a generic function which follows the part calling it
+                for part in parts {
+                    if let tree::ProgramPart::Function(func, _) = part {
+                        if let tree::Function::FuncDeclaration(name, _, ret_ty, _) = func.deref() {
+                            emitter.ret_types.insert(name.to_string(), ret_ty.deref().clone());
+                            emitter.functions.insert(name, func);
+                        }
+                    }
+                }
+                for part in parts {
+                    part.emit(emitter);
+                }
+                emitter.finish_global_ctor();
+            }
             tree::Program::Error => panic!("Error in Program"),
         }
     }
@@ -38,7 +67,7 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::ProgramPart {
         match self {
             // Emit the global variables and functions
             tree::ProgramPart::Statement(stmt) => stmt.emit(emitter),
-            tree::ProgramPart::Function(func) => { func.emit(emitter); },
+            tree::ProgramPart::Function(func, _) => { func.emit(emitter); },
         }
     }
 }
@@ -52,79 +81,48 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Statement {
             tree::Statement::GlobalVariable(vars, _) => {
                 vars.iter().for_each(|var| match var {
                     tree::Variable::VarAssignment(var, expr) => {
-                        match var.as_ref() {
+                        let (name, ty) = match var.as_ref() {
                             tree::Variable::VarDeclaration(name, ty, dims) => {
-                                // Dimensions reference
-                                let dims = get_array_dims(dims, emitter);
-                                let ty = if dims.is_empty() {
-                                    ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum()
-                                } else {
-                                    dims.iter().fold(
-                                        ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum(),
-                                        |acc, len| acc.array_type(len.get_zero_extended_constant().unwrap() as u32)
-                                            .as_basic_type_enum()
-                                    )
-                                };
-                                match ty {
-                                    BasicTypeEnum::ArrayType(_) => {
-                                        // Get the array values
-                                        let assign_vals = expr.deref().iter().map(|expr| expr.emit(emitter)).collect::<Vec<BasicValueEnum>>();
-                                        
-                                        let mut dims = dims.iter();
-                                        let top_size = dims.next().unwrap().get_zero_extended_constant().unwrap() as u32;
-
-                                        let mut arrays = assign_vals.chunks(top_size as usize)
-                                            .map(|a| {
-                                                match a[0] {
-                                                    BasicValueEnum::IntValue(_) => emitter.context.i32_type()
-                                                        .const_array(a.into_iter().map(|v| v.into_int_value()).collect::<Vec<IntValue>>().as_slice()),
-                                                    BasicValueEnum::FloatValue(_) => emitter.context.f32_type()
-                                                        .const_array(a.into_iter().map(|v| v.into_float_value()).collect::<Vec<FloatValue>>().as_slice()),
-                                                    _ => panic!("Not support type of array")
-                                                }
-                                            })
-                                            .collect::<Vec<ArrayValue>>();
-
-                                        let mut array_ty = emitter.context.i32_type().array_type(top_size);
-
-                                        // If it is a multidimensional array
-                                        for dim in dims {
-                                            let size = dim.get_zero_extended_constant().unwrap() as u32;
-                                            arrays = arrays
-                                                .chunks(size as usize)
-                                                .map(|a| array_ty.const_array(a))
-                                                .collect::<Vec<ArrayValue>>();
-                                            array_ty = array_ty.array_type(size);
-                                        }
-                                        // Get the global variable and its pointer and its type (ArrayType if it is an array)
-                                        let global = emitter.module.add_global(array_ty, Some(AddressSpace::default()), name.deref());
-                                        global.set_initializer(&arrays.as_slice()[0]);
-                                    },
-                                    BasicTypeEnum::IntType(_) => {
-                                        let val = expr.deref().first().unwrap().emit(emitter).into_int_value();
-                                        let global = emitter.module.add_global(ty, None, name.deref());
-                                        global.set_initializer(&val);
-                                    },
-                                    BasicTypeEnum::FloatType(_) => {
-                                        let val = expr.deref().first().unwrap().emit(emitter).into_float_value();
-                                        let global = emitter.module.add_global(ty, None, name.deref());
-                                        global.set_initializer(&val);
-                                    },
-                                    _ => {
-                                        unimplemented!()
+                                emitter.globals.insert(name.deref(), ty.deref().clone());
+                                (name, get_declared_type(ty, dims, emitter))
+                            }
+                            tree::Variable::StructDeclaration(struct_name, name, dims) => {
+                                let ty = tree::Value::Struct(struct_name.deref().clone());
+                                emitter.globals.insert(name.deref(), ty.clone());
+                                (name, get_declared_type(&ty, dims, emitter))
+                            }
+                            _ => unreachable!("A global assignment declares its variable"),
+                        };
+                        let global = emitter.module.add_global(ty, None, name.deref());
+                        match expr.deref().as_slice() {
+                            [tree::CompExpr::InitList(items)] if is_const_initializer(&expr[0]) =>
+                                global.set_initializer(&emit_const_initializer(ty, items, emitter)),
+                            [val] if is_const_initializer(val) => global.set_initializer(&emit_coerced(val, ty, emitter)),
+                            // Anything else is evaluated by the module constructor
+                            [val] => {
+                                global.set_initializer(&ty.const_zero());
+                                emitter.enter_global_ctor();
+                                let ptr = global.as_pointer_value();
+                                match val {
+                                    tree::CompExpr::InitList(items) => emit_initializer(ptr, ty, items, emitter),
+                                    _ if ty.is_struct_type() => {
+                                        let src = emit_struct_ptr(val, emitter);
+                                        emitter.emit_struct_copy(ptr, src, ty.into_struct_type(), true);
                                     }
+                                    _ => emit_store(ptr, val, ty, emitter),
                                 }
                             }
-                            _ => unimplemented!()
+                            _ => unreachable!("A global is initialized by a single value"),
                         }
                     },
                     tree::Variable::VarDeclaration(name, ty, dims) => {
+                        emitter.globals.insert(name.deref(), ty.deref().clone());
                         let dims = get_array_dims(dims, emitter);
                         let ty = if dims.is_empty() {
-                            ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum()
+                            emitter.get_llvm_type(ty).unwrap()
                         } else {
                             dims.iter().fold(
-                                ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum(),
+                                emitter.get_llvm_type(ty).unwrap(),
                                 |acc, len| acc.array_type(len
                                     .get_zero_extended_constant().unwrap() as u32)
                                     .as_basic_type_enum()
@@ -133,7 +131,23 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Statement {
                         let global = emitter.module.add_global(ty, None, name.deref());
                         global.set_initializer(&ty.const_zero());
                     },
-                    _ => unimplemented!()
+                    tree::Variable::ConstDeclaration(name, ty, value) => {
+                        emitter.globals.insert(name.deref(), ty.deref().clone());
+                        let folded = value.eval_const(&|name| emitter.get_const(name)).expect("Const value is not a constant");
+                        let init = emitter.const_value(folded, ty);
+                        let global = emitter.module.add_global(init.get_type(), None, name.deref());
+                        global.set_initializer(&init);
+                        global.set_constant(true);
+                        emitter.consts[0].insert(name.deref(), folded);
+                    },
+                    tree::Variable::StructDeclaration(struct_name, name, dims) => {
+                        let ty = tree::Value::Struct(struct_name.deref().clone());
+                        emitter.globals.insert(name.deref(), ty.clone());
+                        let ty = get_declared_type(&ty, dims, emitter);
+                        let global = emitter.module.add_global(ty, None, name.deref());
+                        global.set_initializer(&ty.const_zero());
+                    },
+                    _ => unreachable!("Structs are defined by statements of their own"),
                 });
             }
             tree::Statement::Struct(def, _) => {
@@ -152,34 +166,31 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Variable {
     {
         match self {
             tree::Variable::VarAssignment(var, expr) => {
-                if expr.deref().len() == 1 {
-                    let val: BasicValueEnum = expr.deref().first()?.emit(emitter).into();
-                    let ptr_t = var.emit(emitter).unwrap().0.into_pointer_value();
-                    emitter.builder.build_store(ptr_t, val).expect("Store failed");
-                } else {
+                if let [tree::CompExpr::InitList(items)] = expr.deref().as_slice() {
                     let (ptr, ty) = var.emit(emitter).unwrap();
-
-                    // Get the dimensions information.
-                    let mut arr_typ = ty;
-                    let mut dims = vec![];
-                    while arr_typ.is_array_type() {
-                        dims.push(arr_typ.into_array_type().len() as u32);
-                        arr_typ = arr_typ.into_array_type().get_element_type();
+                    emit_initializer(ptr.into_pointer_value(), ty, items, emitter);
+                } else {
+                    let val = expr.deref().first()?;
+                    if let (Some(array_ty), tree::CompExpr::Variable(src)) = (get_array_type(var, emitter), val) {
+                        let src = src.emit(emitter).unwrap().0.into_pointer_value();
+                        let (ptr_t, _) = var.emit(emitter).unwrap();
+                        let elem = get_var_type(var, emitter).unwrap_or(tree::Value::Null);
+                        emitter.emit_array_copy(ptr_t.into_pointer_value(), src, array_ty, &elem);
+                        return None;
                     }
-
-                    let assign_vals = expr.deref().iter().map(|expr| expr.emit(emitter)).collect::<Vec<BasicValueEnum>>();
-
-                    for (i, val) in assign_vals.iter().enumerate() {
-                        let mut idx_vals = vec![emitter.context.i32_type().const_zero()];
-                        let mut e = i as u32;
-                        for d in dims.iter() {
-                            idx_vals.insert(1, emitter.context.i32_type().const_int((e % d).into(), false));
-                            e /= d;
-                        }
-                        let ptr_t = unsafe {
-                            emitter.builder.build_gep(ty, ptr.into_pointer_value(), idx_vals.as_slice(), "index").unwrap()
-                        };
-                        emitter.builder.build_store(ptr_t, *val).expect("Store failed");
+                    if let Some(tree::Value::Struct(_)) = get_expr_type(val, emitter) {
+                        let src = emit_struct_ptr(val, emitter);
+                        let (ptr_t, ty) = var.emit(emitter).unwrap();
+                        emitter.emit_struct_copy(ptr_t.into_pointer_value(), src, ty.into_struct_type(), false);
+                        return None;
+                    }
+                    let value: BasicValueEnum = val.emit(emitter);
+                    let (ptr_t, ty) = var.emit(emitter).unwrap();
+                    let value = coerce(val, value, ty, emitter);
+                    if is_string(val, emitter) {
+                        emitter.emit_string_store(ptr_t.into_pointer_value(), value);
+                    } else {
+                        emitter.builder.build_store(ptr_t.into_pointer_value(), value).expect("Store failed");
                     }
                 }
                 None
@@ -209,47 +220,87 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Variable {
                     }, arr_typ.into()))
                 }
             }
-            tree::Variable::VarDeclaration(name, ty, dims) => {
+            tree::Variable::VarDeclaration(name, value_ty, dims) => {
                 let ty = if dims.is_empty() {
-                    ty.deref().emit(emitter)?.get_type().into()
+                    emitter.get_llvm_type(value_ty)?
                 } else {
-                    let dims = get_array_dims(dims, emitter);
-                    dims.iter().fold(
-                        emitter.context.i32_type().as_basic_type_enum(),
-                        |acc, len| acc.array_type(len.get_zero_extended_constant().unwrap() as u32)
-                            .as_basic_type_enum()
-                    )
+                    get_declared_type(value_ty, dims, emitter)
                 };
                 let new_var = emitter.builder.build_alloca(ty, name.deref()).unwrap();
-                emitter.scope.last_mut().unwrap().insert(name.deref(), (new_var, ty));
+                if let tree::Value::String(_) = value_ty.deref() {
+                    emitter.add_string_slots(new_var, ty, count_scalars(ty) as u64);
+                }
+                emitter.scope.last_mut().unwrap().insert(name.deref(), (new_var, ty, value_ty.deref().clone()));
+                None
+            }
+            tree::Variable::ConstDeclaration(name, value_ty, value) => {
+                let folded = value.eval_const(&|name| emitter.get_const(name)).expect("Const value is not a constant");
+                let init = emitter.const_value(folded, value_ty);
+                let new_var = emitter.builder.build_alloca(init.get_type(), name.deref()).unwrap();
+                emitter.builder.build_store(new_var, init).expect("Store failed");
+                emitter.scope.last_mut().unwrap().insert(name.deref(), (new_var, init.get_type(), value_ty.deref().clone()));
+                emitter.consts.last_mut().unwrap().insert(name.deref(), folded);
+                None
+            }
+            tree::Variable::FormalParameter(_, ty, dims) => match ty.deref() {
+                // Arrays decay to a pointer to their first element, the array type
+                // has an outermost length of 0 when it is omitted
+                _ if !dims.is_empty() => {
+                    let elem_ty = emitter.get_llvm_type(ty)?;
+                    let array_ty = dims.iter().rev().fold(elem_ty, |acc, len| acc.array_type(*len as u32).as_basic_type_enum());
+                    Some((emitter.context.ptr_type(AddressSpace::default()).const_null().as_basic_value_enum(), array_ty))
+                }
+                // Structs are passed by pointer, see `Azuki::type_attribute`
+                tree::Value::Struct(name) => {
+                    let struct_ty = emitter.module.get_struct_type(name)?;
+                    Some((emitter.context.ptr_type(AddressSpace::default()).const_null().as_basic_value_enum(), struct_ty.as_basic_type_enum()))
+                }
+                _ => Some((emitter.get_llvm_type(ty)?.const_zero(), emitter.context.i32_type().into())),
+            },
+            tree::Variable::StructForward(name) => {
+                if emitter.module.get_struct_type(name).is_none() {
+                    emitter.context.opaque_struct_type(name);
+                }
                 None
             }
-            tree::Variable::FormalParameter(_, ty, _) => Some((ty.deref().emit(emitter).unwrap(), emitter.context.i32_type().into())),
             tree::Variable::StructDefinition(name, vars) => {
+                // The struct starts opaque so that its fields can point to itself,
+                // a forward declaration may have created it already
+                let struct_type = emitter.module.get_struct_type(name)
+                    .unwrap_or_else(|| emitter.context.opaque_struct_type(name));
                 let mut field_hashmap = HashMap::new();
                 let mut struct_types = vec![];
                 for (i, var) in vars.iter().enumerate() {
                     match var {
-                        tree::Variable::VarDeclaration(field_name, ty, dims) => {
+                        tree::Variable::VarDeclaration(field_name, field_ty, dims) => {
                             let ty = if dims.is_empty() {
-                                ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum()
+                                emitter.get_llvm_type(field_ty.deref()).unwrap()
                             } else {
                                 let dims = get_array_dims(dims, emitter);
                                 dims.iter().fold(
-                                    ty.deref().emit(emitter).unwrap().get_type().as_basic_type_enum(),
+                                    emitter.get_llvm_type(field_ty.deref()).unwrap(),
                                     |acc, len| acc.array_type(len.get_zero_extended_constant().unwrap() as u32)
                                         .as_basic_type_enum()
                                 )
                             };
-                            field_hashmap.insert(field_name.deref().as_str(), i);
+                            field_hashmap.insert(field_name.deref().as_str(), (i, field_ty.deref().clone()));
                             struct_types.push(ty);
                         },
-                        _ => unimplemented!()
+                        // Nested struct values, the inner struct is complete by now
+                        tree::Variable::StructDeclaration(struct_name, field_name, dims) => {
+                            let inner = emitter.module.get_struct_type(struct_name).unwrap().as_basic_type_enum();
+                            let dims = get_array_dims(dims, emitter);
+                            let ty = dims.iter().fold(inner,
+                                |acc, len| acc.array_type(len.get_zero_extended_constant().unwrap() as u32).as_basic_type_enum()
+                            );
+                            field_hashmap.insert(field_name.deref().as_str(), (i, tree::Value::Struct(struct_name.deref().clone())));
+                            struct_types.push(ty);
+                        },
+                        // Initializers and constants of fields are rejected by the analyser
+                        _ => unreachable!("Field {} is not a declaration", var.get_name()),
                     }
                 }
                 emitter.struct_fields.insert(name.deref(), field_hashmap);
-                // We have to do it this way to index a struct type by its name from LLVM context
-                let struct_type = emitter.context.opaque_struct_type(name.deref());
                 struct_type.set_body(struct_types.as_slice(), false);
 
                 None
@@ -266,7 +317,14 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Variable {
                     )
                 };
                 let new_var = emitter.builder.build_alloca(ty, inst.deref()).unwrap();
-                emitter.scope.last_mut().unwrap().insert(inst.deref(), (new_var, ty));
+                // Assigning a string field releases the previous value, which has to be valid,
+                // and the string fields are released when the struct goes out of scope
+                let value = tree::Value::Struct(structname.deref().clone());
+                if emitter.holds_strings(&value) {
+                    emitter.builder.build_store(new_var, ty.const_zero()).expect("Store failed");
+                    emitter.add_string_fields(new_var, ty, &value);
+                }
+                emitter.scope.last_mut().unwrap().insert(inst.deref(), (new_var, ty, value));
                 None
             }
             tree::Variable::StructReference(vars) => {
@@ -276,12 +334,12 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Variable {
                             return member.emit(emitter);
                         }
 
-                        let struct_ptr = base.unwrap().0.into_pointer_value();
-                        let struct_type = emitter.get_var(struct_ptr.get_name().to_str().unwrap())?.1.into_struct_type();
+                        let (struct_ptr, struct_type) = base.unwrap();
+                        let (struct_ptr, struct_type) = (struct_ptr.into_pointer_value(), struct_type.into_struct_type());
                         let field_hashmap = emitter.struct_fields.get(struct_type.get_name()?.to_str().unwrap()).unwrap();
 
                         if let tree::Variable::VarReference(name, dims) = member {
-                            let field_idx = field_hashmap.get(name.deref().as_str()).unwrap();
+                            let (field_idx, _) = field_hashmap.get(name.deref().as_str()).unwrap();
                             unsafe {
                                 let field_ptr = emitter.builder
                                     .build_struct_gep(struct_type, struct_ptr, *field_idx as u32, name.deref()).unwrap();
@@ -292,13 +350,20 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Variable {
                                 } else {
                                     let mut idx_vals = vec![emitter.context.i32_type().const_zero()];
                                     idx_vals.extend(dims.deref().iter().map(|dim| dim.emit(emitter).into_int_value()));
-                                    Some((emitter.builder.build_in_bounds_gep(field_ty, field_ptr, idx_vals.as_ref(), "index").unwrap().as_basic_value_enum(), field_ty))
+                                    // Each index strips one array level, so that nested struct members can follow
+                                    let elem_ty = dims.iter().fold(field_ty, |acc, _| acc.into_array_type().get_element_type());
+                                    Some((emitter.builder.build_in_bounds_gep(field_ty, field_ptr, idx_vals.as_ref(), "index").unwrap().as_basic_value_enum(), elem_ty))
                                 }
                             }
                         } else { None }
                     }
                 )
             }
+            tree::Variable::Dereference(expr) => {
+                let pointee = get_pointee_type(expr, emitter)?;
+                let ptr = expr.emit(emitter);
+                Some((ptr, emitter.get_llvm_type(&pointee)?))
+            }
             _ => panic!("Error in Variable"),
         }
     }
@@ -311,59 +376,224 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Function {
     {
         match self {
             tree::Function::FuncDeclaration(name, params, ret_ty, body) => {
-                emitter.scope.push(HashMap::new());
-
-                let paras_ty = params.iter().map(|param|
-                    param.emit(emitter).unwrap().0.get_type().into()
-                ).collect::<Vec<BasicMetadataTypeEnum>>();
+                emitter.enter_scope();
+                emitter.str_mark = None;
+                emitter.str_temps = false;
 
-                let ret_ty = ret_ty.deref().emit(emitter)?.get_type().fn_type(paras_ty.as_ref(), false);
-                let func = emitter.module.add_function(name, ret_ty, None);
+                let func = declare_function(name, params, ret_ty, emitter)?;
+                let sret_ty = match ret_ty.deref() {
+                    tree::Value::Struct(s) => emitter.module.get_struct_type(s),
+                    _ => None
+                };
+                let offset = sret_ty.is_some() as u32;
+                emitter.ret_ptr = sret_ty.map(|ty| {
+                    func.add_attribute(AttributeLoc::Param(0), emitter.type_attribute("sret", ty));
+                    let ret_ptr = func.get_nth_param(0).unwrap().into_pointer_value();
+                    ret_ptr.set_name("sret");
+                    ret_ptr
+                });
                 let entry = emitter.context.append_basic_block(func, "entry");
                 emitter.builder.position_at_end(entry);
 
                 for (i, param) in params.iter().enumerate() {
-                     if let tree::Variable::FormalParameter(name, _, _) = param {
-                         let value = func.get_nth_param(i as u32).unwrap();
+                     if let tree::Variable::FormalParameter(name, ty, dims) = param {
+                         let value = func.get_nth_param(i as u32 + offset).unwrap();
                          value.set_name(name.deref());
 
+                         if !dims.is_empty() {
+                             // Indexing goes through the decayed pointer directly
+                             let array_ty = param.emit(emitter).unwrap().1;
+                             emitter.scope.last_mut().unwrap().insert(name, (value.into_pointer_value(), array_ty, ty.deref().clone()));
+                             continue;
+                         }
+
+                         if let tree::Value::Struct(s) = ty.deref() {
+                             // The caller passes a copy, which is used in place and owns
+                             // references of its own to its strings
+                             let struct_ty = emitter.module.get_struct_type(s).unwrap();
+                             func.add_attribute(AttributeLoc::Param(i as u32 + offset), emitter.type_attribute("byval", struct_ty));
+                             emitter.emit_string_fields("spl_str_retain_fields", value.into_pointer_value(), struct_ty.into(), ty);
+                             emitter.add_string_fields(value.into_pointer_value(), struct_ty.into(), ty);
+                             emitter.scope.last_mut().unwrap().insert(name, (value.into_pointer_value(), struct_ty.into(), ty.deref().clone()));
+                             continue;
+                         }
+
                          let ptr = emitter.builder.build_alloca(value.get_type(), name.deref()).unwrap();
-                         emitter.builder.build_store(ptr, value).expect("Store failed");
-                         emitter.scope.last_mut().unwrap().insert(name, (ptr, value.get_type().into()));
+                         if let tree::Value::String(_) = ty.deref() {
+                             // The parameter owns a reference of its own, like any variable
+                             emitter.add_string_slots(ptr, value.get_type(), 1);
+                             emitter.emit_string_store(ptr, value);
+                         } else {
+                             emitter.builder.build_store(ptr, value).expect("Store failed");
+                         }
+                         emitter.scope.last_mut().unwrap().insert(name, (ptr, value.get_type(), ty.deref().clone()));
                     } else {
                         panic!("Error in Function");
                     };
                 }
 
                 body.emit(emitter);
+                // Void functions return when they reach the end of their body,
+                // and `main` returns 0, like in C
+                if emitter.no_terminator() {
+                    match func.get_type().get_return_type() {
+                        None => {
+                            emitter.release_strings(0);
+                            emitter.builder.build_return(None).expect("Error in Function");
+                        }
+                        Some(ty) if name.as_str() == "main" => {
+                            emitter.builder.build_return(Some(&ty.const_zero())).expect("Error in Function");
+                        }
+                        Some(_) => {}
+                    }
+                }
                 let last_bb = emitter.builder.get_insert_block().unwrap();
                 if last_bb.get_last_instruction().is_none() { // empty block, removed
                     last_bb.remove_from_function().expect("Cannot remove last block");
                 }
-                emitter.scope.pop();
+                emitter.exit_scope();
                 None
             },
-            tree::Function::FuncReference(name, params) => {
-                let args = params.iter().map(|param| param.emit(emitter).into()).collect::<Vec<BasicMetadataValueEnum>>();
-                if (*name).as_str().eq("printf") {
+            // Overloads and instances of generic functions are named by the analyser
+            tree::Function::FuncReference(name, params, _) => {
+                let name = name.as_str();
+                let mut args = Vec::new();
+                let mut byval = Vec::new();
+                let c_call = name.eq("printf") || name.eq("scanf");
+                for param in params.iter() {
+                    match get_expr_type(param, emitter) {
+                        // Format strings and other literals are passed to C as they are
+                        Some(tree::Value::String(s)) if c_call && matches!(param.deref(), tree::CompExpr::Value(_)) =>
+                            args.push(emitter.emit_global_string(&mut s.to_owned(), "").into()),
+                        Some(tree::Value::String(_)) if c_call => {
+                            let val = param.emit(emitter);
+                            args.push(emitter.emit_runtime_call("spl_str_cstr", &[val.into()]).unwrap().into());
+                        }
+                        Some(tree::Value::Struct(s)) => {
+                            byval.push((args.len() as u32, emitter.module.get_struct_type(&s).unwrap()));
+                            args.push(emit_struct_ptr(param, emitter).into());
+                        }
+                        _ => match param.deref() {
+                            // Arrays are passed by the address of their first element
+                            tree::CompExpr::Variable(var) if !is_string_index(var, emitter) => {
+                                let (ptr, ty) = var.emit(emitter).unwrap();
+                                if ty.is_array_type() {
+                                    args.push(ptr.into());
+                                } else {
+                                    args.push(emitter.builder.build_load(ty, ptr.into_pointer_value(), &var.get_name()).unwrap().into());
+                                }
+                            }
+                            _ => args.push(param.emit(emitter).into()),
+                        }
+                    }
+                }
+                if name.eq("printf") {
+                    // Variadic arguments are promoted, floats are passed as doubles
+                    // and characters and short integers as ints
+                    let double = emitter.context.f64_type().as_basic_type_enum();
+                    let int = emitter.context.i32_type().as_basic_type_enum();
+                    for (arg, param) in args.iter_mut().zip(params.iter()) {
+                        match *arg {
+                            BasicMetadataValueEnum::FloatValue(v) =>
+                                *arg = coerce(param, v.as_basic_value_enum(), double, emitter).into(),
+                            BasicMetadataValueEnum::IntValue(v) if (2..32).contains(&v.get_type().get_bit_width()) =>
+                                *arg = coerce(param, v.as_basic_value_enum(), int, emitter).into(),
+                            _ => {}
+                        }
+                    }
                     emitter.emit_printf_call(args.as_slice());
                     return None;
-                } else if (*name).as_str().eq("scanf") {
+                } else if name.eq("scanf") {
                     emitter.emit_scanf_call(args.as_slice());
                     return None;
                 }
 
-                let func = emitter.module.get_function((*name).as_str()).expect("Function undeclared");
-                Some(emitter.builder.build_call(func, args.as_slice(), (*name).as_str()).unwrap()
-                    .try_as_basic_value()
-                    .left()
-                    .unwrap_or(emitter.context.i32_type().const_int(0, false).as_basic_value_enum()))
+                // Functions of the program that share the name of a builtin are
+                // overloads of it, and renamed by the analyser
+                let func = match (emitter.module.get_function(name), emitter.functions.get(name).copied()) {
+                    (Some(func), _) => func,
+                    (None, Some(tree::Function::FuncDeclaration(_, inputs, ret_ty, _))) =>
+                        declare_function(name, inputs, ret_ty, emitter).expect("Function undeclared"),
+                    _ => match BUILTINS.iter().find(|(builtin, ..)| *builtin == name) {
+                        Some((_, runtime, _)) => emitter.runtime_fn(runtime),
+                        None => panic!("Function undeclared"),
+                    },
+                };
+                let sret_ty = match emitter.ret_types.get(name) {
+                    Some(tree::Value::Struct(s)) => emitter.module.get_struct_type(s),
+                    _ => None
+                };
+                // Numeric arguments are converted to the type of their parameter
+                let param_types = func.get_type().get_param_types();
+                let offset = sret_ty.is_some() as usize;
+                for (i, arg) in args.iter_mut().enumerate() {
+                    if let (Ok(v), Some(&ty)) = (BasicValueEnum::try_from(*arg), param_types.get(i + offset)) {
+                        *arg = coerce(&params[i], v, ty, emitter).into();
+                    }
+                }
+                if let Some(struct_ty) = sret_ty {
+                    let ret_ptr = emitter.builder.build_alloca(struct_ty, "sret").unwrap();
+                    args.insert(0, ret_ptr.into());
+                    let call = emitter.builder.build_call(func, args.as_slice(), "").unwrap();
+                    call.add_attribute(AttributeLoc::Param(0), emitter.type_attribute("sret", struct_ty));
+                    for (i, ty) in byval {
+                        call.add_attribute(AttributeLoc::Param(i + 1), emitter.type_attribute("byval", ty));
+                    }
+                    // The strings of the result are left in the temporary pool, like returned strings
+                    if let Some(ty @ tree::Value::Struct(_)) = emitter.ret_types.get(name).cloned() {
+                        if emitter.holds_strings(&ty) {
+                            emitter.emit_string_fields("spl_str_keep_fields", ret_ptr, struct_ty.into(), &ty);
+                            emitter.str_temps = true;
+                        }
+                    }
+                    return Some(emitter.builder.build_load(struct_ty, ret_ptr, name).unwrap());
+                }
+                let call = emitter.builder.build_call(func, args.as_slice(), name).unwrap();
+                for (i, ty) in byval {
+                    call.add_attribute(AttributeLoc::Param(i), emitter.type_attribute("byval", ty));
+                }
+                // A returned string is left in the temporary pool
+                if let Some(tree::Value::String(_)) = emitter.ret_types.get(name) {
+                    emitter.str_temps = true;
+                }
+                // Void functions have no result
+                call.try_as_basic_value().left()
             },
+            // Only the instances of generic functions are emitted
+            tree::Function::Generic(..) => None,
             tree::Function::Error => panic!("Error in Function")
         }
     }
 }
 
+// Declare a function with its parameter and return types, or get the earlier
+// declaration made by a call coming before it. A struct result is returned
+// through a leading `sret` parameter.
+fn declare_function<'ast, 'ctx>(name: &str, params: &'ast [tree::Variable], ret_ty: &tree::Value, emitter: &mut Azuki<'ast, 'ctx>) -> Option<FunctionValue<'ctx>>
+    where 'ast: 'ctx
+{
+    if let Some(func) = emitter.module.get_function(name) {
+        return Some(func);
+    }
+    let mut paras_ty = params.iter().map(|param|
+        param.emit(emitter).unwrap().0.get_type().into()
+    ).collect::<Vec<BasicMetadataTypeEnum>>();
+
+    let sret_ty = match ret_ty {
+        tree::Value::Struct(s) => emitter.module.get_struct_type(s),
+        _ => None
+    };
+    let fn_ty = match sret_ty {
+        Some(_) => {
+            paras_ty.insert(0, emitter.context.ptr_type(AddressSpace::default()).into());
+            emitter.context.void_type().fn_type(paras_ty.as_ref(), false)
+        }
+        None if *ret_ty == tree::Value::Null => emitter.context.void_type().fn_type(paras_ty.as_ref(), false),
+        None => emitter.get_llvm_type(ret_ty)?.fn_type(paras_ty.as_ref(), false)
+    };
+    Some(emitter.module.add_function(name, fn_ty, None))
+}
+
 impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Body {
     type Output = ();
     fn emit(&'ast self, emitter: &mut Azuki<'ast, 'ctx>) -> Self::Output
@@ -372,9 +602,19 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Body {
         match self {
             tree::Body::Body(stmts) => {
                 // nested scope
-                emitter.scope.push(HashMap::new());
-                stmts.iter().for_each(|stmt| stmt.emit(emitter));
-                emitter.scope.pop();
+                emitter.enter_scope();
+                for stmt in stmts {
+                    // Statements after a return, break or continue are unreachable
+                    if !emitter.no_terminator() {
+                        break;
+                    }
+                    stmt.emit(emitter);
+                    emitter.drain_strings();
+                }
+                if emitter.no_terminator() {
+                    emitter.release_strings(emitter.strings.len() - 1);
+                }
+                emitter.exit_scope();
             }
             _ => panic!("Error in Body"),
         }
@@ -388,10 +628,27 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
     {
         match self {
             tree::Expr::Return(expr, ..) => {
+                // The string variables of the function are released before leaving it
                 if expr.eq(&tree::CompExpr::Value(tree::Value::Null)) {
+                    emitter.release_strings(0);
+                    emitter.builder.build_return(None).expect("Error in Expr");
+                } else if let Some(ret_ptr) = emitter.ret_ptr {
+                    let src = emit_struct_ptr(expr, emitter);
+                    let struct_ty = get_expr_type(expr, emitter).and_then(|t| emitter.get_llvm_type(&t)).unwrap();
+                    emitter.emit_struct_copy(ret_ptr, src, struct_ty.into_struct_type(), true);
+                    emitter.release_strings(0);
                     emitter.builder.build_return(None).expect("Error in Expr");
                 } else {
-                    let ret = expr.emit(emitter);
+                    let func = emitter.builder.get_insert_block().and_then(|bb| bb.get_parent()).unwrap();
+                    let mut ret = match func.get_type().get_return_type() {
+                        Some(ty) => emit_coerced(expr, ty, emitter),
+                        None => expr.emit(emitter),
+                    };
+                    if is_string(expr, emitter) {
+                        // Outlives the variable it may come from, until the caller drains it
+                        ret = emitter.emit_runtime_call("spl_str_keep", &[ret.into()]).unwrap();
+                    }
+                    emitter.release_strings(0);
                     emitter.builder.build_return(Some(&ret)).expect("Error in Expr");
                 }
             },
@@ -405,6 +662,7 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                 match if_expr {
                     tree::If::IfExpr(cond, if_body) => {
                         let cond = cond.emit(emitter);
+                        emitter.drain_strings();
                         let func = emitter.builder.get_insert_block().unwrap().get_parent().unwrap();
                         let then_bb = emitter.context.append_basic_block(func, "then");
                         let merge_bb = emitter.context.append_basic_block(func, "merge");
@@ -419,6 +677,7 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                     }
                     tree::If::IfElseExpr(cond, if_body, else_body) => {
                         let cond = cond.emit(emitter);
+                        emitter.drain_strings();
                         let func = emitter.builder.get_insert_block().unwrap().get_parent().unwrap();
                         let then_bb = emitter.context.append_basic_block(func, "then");
                         let else_bb = emitter.context.append_basic_block(func, "else");
@@ -442,7 +701,7 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                     _ => panic!("Error in Expr"),
                 }
             }
-            tree::Expr::Loop(loop_expr, _) => {
+            tree::Expr::Loop(loop_expr, label, _) => {
                 match loop_expr {
                     tree::Loop::WhileExpr(cond, body) => {
                         let func = emitter.builder.get_insert_block().unwrap().get_parent().unwrap();
@@ -453,11 +712,14 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                         emitter.builder.build_unconditional_branch(cond_bb).expect("Error in WhileLoop");
                         emitter.builder.position_at_end(cond_bb);
                         let cond = cond.emit(emitter);
+                        emitter.drain_strings();
                         emitter.builder.build_conditional_branch(cond.into_int_value(), body_bb, merge_bb).expect("Error in WhileLoop");
 
                         emitter.loops.push(Loop {
                             loop_head: cond_bb,
                             after_loop: merge_bb,
+                            label: label.as_deref(),
+                            depth: emitter.scope.len(),
                         }); // Used to document the loop information
                         emitter.builder.position_at_end(body_bb);
                         body.emit(emitter);
@@ -481,15 +743,19 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                         emitter.builder.build_unconditional_branch(init_bb).expect("Error in ForLoop");
                         emitter.builder.position_at_end(init_bb);
                         init.emit(emitter);
+                        emitter.drain_strings();
                         emitter.builder.build_unconditional_branch(cond_bb).expect("Error in ForLoop");
 
                         emitter.builder.position_at_end(cond_bb);
                         let cond = cond.emit(emitter);
+                        emitter.drain_strings();
                         emitter.builder.build_conditional_branch(cond.into_int_value(), body_bb, merge_bb).expect("Error in ForLoop");
 
                         emitter.loops.push(Loop {
                             loop_head: step_bb,
                             after_loop: merge_bb,
+                            label: label.as_deref(),
+                            depth: emitter.scope.len(),
                         }); // Used to document the loop information
 
                         emitter.builder.position_at_end(body_bb);
@@ -501,6 +767,7 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
 
                         emitter.builder.position_at_end(step_bb);
                         step.emit(emitter);
+                        emitter.drain_strings();
                         emitter.builder.build_unconditional_branch(cond_bb).expect("Error in ForLoop");
 
                         emitter.loops.pop();
@@ -510,17 +777,25 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Expr {
                     _ => panic!("Error in Expr"),
                 }
             }
-            tree::Expr::Break(_) => {
-                let loop_info = emitter.loops.last().expect("Error in Break");
-                emitter.builder.build_unconditional_branch(loop_info.after_loop).expect("Error in Break");
+            tree::Expr::Break(label, _) => {
+                let loop_info = emitter.get_loop(label.as_deref()).expect("Error in Break");
+                let (after_loop, depth) = (loop_info.after_loop, loop_info.depth);
+                emitter.release_strings(depth);
+                emitter.builder.build_unconditional_branch(after_loop).expect("Error in Break");
             }
-            tree::Expr::Continue(_) => {
-                let loop_info = emitter.loops.last().expect("Error in Continue");
-                emitter.builder.build_unconditional_branch(loop_info.loop_head).expect("Error in Continue");
+            tree::Expr::Continue(label, _) => {
+                let loop_info = emitter.get_loop(label.as_deref()).expect("Error in Continue");
+                let (loop_head, depth) = (loop_info.loop_head, loop_info.depth);
+                emitter.release_strings(depth);
+                emitter.builder.build_unconditional_branch(loop_head).expect("Error in Continue");
             }
             tree::Expr::Body(body, _ ) => {
                 body.emit(emitter);
             }
+            tree::Expr::Delete(expr, _) => {
+                let ptr = expr.emit(emitter).into_pointer_value();
+                emitter.builder.build_free(ptr).expect("Error in Delete");
+            }
             tree::Expr::Error => panic!("Error in Expr"),
         }
     }
@@ -533,48 +808,89 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::CompExpr {
     {
         match self {
             tree::CompExpr::Value(val) => val.emit(emitter).unwrap(),
+            tree::CompExpr::Variable(var @ tree::Variable::VarReference(name, dims))
+                if dims.is_empty() && emitter.get_const(name).is_some() => {
+                let ty = get_var_type(var, emitter).unwrap();
+                emitter.const_value(emitter.get_const(name).unwrap(), &ty)
+            }
+            // Characters of a string are read through the runtime library
+            tree::CompExpr::Variable(var @ tree::Variable::VarReference(name, dims)) if is_string_index(var, emitter) => {
+                let (ptr, ty) = emitter.get_var(name).unwrap();
+                let (index, levels) = dims.split_last().unwrap();
+                let slot = if levels.is_empty() {
+                    ptr
+                } else {
+                    let mut idx_vals = vec![emitter.context.i32_type().const_zero()];
+                    idx_vals.extend(levels.iter().map(|dim| dim.emit(emitter).into_int_value()));
+                    unsafe { emitter.builder.build_in_bounds_gep(ty, ptr, idx_vals.as_ref(), "index").unwrap() }
+                };
+                let ptr_type = emitter.context.ptr_type(AddressSpace::default());
+                let string = emitter.builder.build_load(ptr_type, slot, name).unwrap();
+                let index = emit_coerced(index, emitter.context.i32_type().into(), emitter);
+                emitter.emit_runtime_call("spl_str_at", &[string.into(), index.into()]).unwrap()
+            }
             tree::CompExpr::Variable(var) => {
                 let (ptr, ty) = var.emit(emitter).unwrap();
                 emitter.builder.build_load(ty, ptr.into_pointer_value(), &var.get_name()).unwrap().as_basic_value_enum()
             },
             tree::CompExpr::FuncCall(function) => {
-                function.emit(emitter).unwrap_or(
-                    emitter.context.i32_type().const_int(0, false).as_basic_value_enum()
-                )
+                function.emit(emitter).expect("The analyser rejects values of void calls")
             },
             tree::CompExpr::UnaryOperation(op, expr) => {
-                let var = if let tree::CompExpr::Variable(var) = expr.deref() {
-                    var
-                } else {
-                    panic!("Must be a pointer");
-                };
                 match op {
-                    tree::UnaryOperator::Ref => var.emit(emitter).unwrap().0,
+                    tree::UnaryOperator::Ref => {
+                        if let tree::CompExpr::Variable(var) = expr.deref() {
+                            var.emit(emitter).unwrap().0
+                        } else {
+                            panic!("Must be a variable");
+                        }
+                    }
                     tree::UnaryOperator::Deref => {
-                        let (ptr, ty) = var.emit(emitter).unwrap();
-                        emitter.builder.build_load(ty, ptr.into_pointer_value(), &var.get_name()).unwrap().as_basic_value_enum()
+                        let ty = get_pointee_type(expr, emitter).and_then(|t| emitter.get_llvm_type(&t)).expect("Must be a pointer");
+                        let ptr = expr.emit(emitter).into_pointer_value();
+                        emitter.builder.build_load(ty, ptr, "deref").unwrap().as_basic_value_enum()
                     }
                     _ => panic!("Operator not supported in CompExpr"),
                 }
             }
+            tree::CompExpr::BinaryOperation(lhs, _, rhs) if is_string(lhs, emitter) => {
+                // The analyser only lets strings be concatenated
+                let (lhs, rhs) = (lhs.emit(emitter), rhs.emit(emitter));
+                emitter.emit_runtime_call("spl_str_concat", &[lhs.into(), rhs.into()]).unwrap()
+            }
             tree::CompExpr::BinaryOperation(lhs, op, rhs) => {
-                let lhs = (*lhs.deref()).emit(emitter);
-                let rhs = (*rhs.deref()).emit(emitter);
+                // Pointer arithmetic is scaled by the size of the pointee
+                let pointee = get_pointee_type(lhs, emitter)
+                    .or_else(|| get_pointee_type(rhs, emitter))
+                    .and_then(|t| emitter.get_llvm_type(&t));
+                let (lhs, rhs, signed) = emit_operands(lhs, rhs, emitter);
                 match op {
                     tree::BinaryOperator::Add => {
-                        match lhs {
-                            BasicValueEnum::IntValue(lhs) =>
+                        match (lhs, rhs) {
+                            (BasicValueEnum::PointerValue(ptr), BasicValueEnum::IntValue(offset)) |
+                            (BasicValueEnum::IntValue(offset), BasicValueEnum::PointerValue(ptr)) => unsafe {
+                                emitter.builder.build_in_bounds_gep(pointee.unwrap(), ptr, &[offset], "addtmp").unwrap().as_basic_value_enum()
+                            },
+                            (BasicValueEnum::IntValue(lhs), _) =>
                                 emitter.builder.build_int_add(lhs, rhs.into_int_value(), "addtmp").unwrap().as_basic_value_enum(),
-                            BasicValueEnum::FloatValue(lhs) =>
+                            (BasicValueEnum::FloatValue(lhs), _) =>
                                 emitter.builder.build_float_add(lhs, rhs.into_float_value(), "addtmp").unwrap().as_basic_value_enum(),
                             _ => panic!("Error in CompExpr Add"),
                         }
                     }
                     tree::BinaryOperator::Sub => {
-                        match lhs {
-                            BasicValueEnum::IntValue(lhs) =>
+                        match (lhs, rhs) {
+                            (BasicValueEnum::PointerValue(ptr), BasicValueEnum::IntValue(offset)) => unsafe {
+                                let offset = emitter.builder.build_int_neg(offset, "negtmp").unwrap();
+                                emitter.builder.build_in_bounds_gep(pointee.unwrap(), ptr, &[offset], "subtmp").unwrap().as_basic_value_enum()
+                            },
+                            (BasicValueEnum::PointerValue(lhs), BasicValueEnum::PointerValue(rhs)) => {
+                                let diff = emitter.builder.build_ptr_diff(pointee.unwrap(), lhs, rhs, "difftmp").unwrap();
+                                emitter.builder.build_int_truncate(diff, emitter.context.i32_type(), "subtmp").unwrap().as_basic_value_enum()
+                            }
+                            (BasicValueEnum::IntValue(lhs), _) =>
                                 emitter.builder.build_int_sub(lhs, rhs.into_int_value(), "subtmp").unwrap().as_basic_value_enum(),
-                            BasicValueEnum::FloatValue(lhs) =>
+                            (BasicValueEnum::FloatValue(lhs), _) =>
                                 emitter.builder.build_float_sub(lhs, rhs.into_float_value(), "subtmp").unwrap().as_basic_value_enum(),
                             _ => panic!("Error in CompExpr Sub"),
                         }
@@ -590,6 +906,8 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::CompExpr {
                     }
                     tree::BinaryOperator::Div => {
                         match lhs {
+                            BasicValueEnum::IntValue(lhs) if signed =>
+                                emitter.builder.build_int_signed_div(lhs, rhs.into_int_value(), "divtmp").unwrap().as_basic_value_enum(),
                             BasicValueEnum::IntValue(lhs) =>
                                 emitter.builder.build_int_unsigned_div(lhs, rhs.into_int_value(), "divtmp").unwrap().as_basic_value_enum(),
                             BasicValueEnum::FloatValue(lhs) =>
@@ -599,8 +917,10 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::CompExpr {
                     }
                     tree::BinaryOperator::Mod => {
                         match lhs {
-                            BasicValueEnum::IntValue(lhs) =>
+                            BasicValueEnum::IntValue(lhs) if signed =>
                                 emitter.builder.build_int_signed_rem(lhs, rhs.into_int_value(), "modtmp").unwrap().as_basic_value_enum(),
+                            BasicValueEnum::IntValue(lhs) =>
+                                emitter.builder.build_int_unsigned_rem(lhs, rhs.into_int_value(), "modtmp").unwrap().as_basic_value_enum(),
                             BasicValueEnum::FloatValue(lhs) =>
                                 emitter.builder.build_float_rem(lhs, rhs.into_float_value(), "modtmp").unwrap().as_basic_value_enum(),
                             _ => panic!("Error in CompExpr Mod"),
@@ -629,6 +949,21 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::CompExpr {
                     }
                 _ => panic!("Error in CompExpr BinaryOperation"),
             }}
+            tree::CompExpr::New(ty, count) => {
+                let ty = emitter.get_llvm_type(ty).expect("Cannot allocate void");
+                match count {
+                    Some(count) => {
+                        let count = count.emit(emitter).into_int_value();
+                        emitter.builder.build_array_malloc(ty, count, "newtmp").unwrap().as_basic_value_enum()
+                    }
+                    None => emitter.builder.build_malloc(ty, "newtmp").unwrap().as_basic_value_enum(),
+                }
+            }
+            tree::CompExpr::SizeOf(ty) => {
+                // The size is a target dependent constant, folded by LLVM
+                let size = emitter.get_llvm_type(ty).and_then(|t| t.size_of()).expect("Cannot take the size of void");
+                emitter.builder.build_int_truncate(size, emitter.context.i32_type(), "sizetmp").unwrap().as_basic_value_enum()
+            }
             _ => panic!("Error in CompExpr Unsupported"),
         }
     }
@@ -663,48 +998,72 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::CondExpr {
                     _ => panic!("Operator not supported in CondExpr"),
                 }
             }
+            // Strings compare lexicographically, by the sign of `spl_str_cmp`
+            tree::CondExpr::Condition(lhs, op, rhs) if is_string(lhs, emitter) => {
+                let (lhs, rhs) = (lhs.emit(emitter), rhs.emit(emitter));
+                let cmp = emitter.emit_runtime_call("spl_str_cmp", &[lhs.into(), rhs.into()]).unwrap().into_int_value();
+                let predicate = match op {
+                    tree::JudgeOperator::GT => inkwell::IntPredicate::SGT,
+                    tree::JudgeOperator::GE => inkwell::IntPredicate::SGE,
+                    tree::JudgeOperator::LT => inkwell::IntPredicate::SLT,
+                    tree::JudgeOperator::LE => inkwell::IntPredicate::SLE,
+                    tree::JudgeOperator::EQ => inkwell::IntPredicate::EQ,
+                    tree::JudgeOperator::NE => inkwell::IntPredicate::NE,
+                    _ => panic!("Error in CondExpr"),
+                };
+                emitter.builder.build_int_compare(predicate, cmp, cmp.get_type().const_zero(), "cmptmp").unwrap().as_basic_value_enum()
+            }
             tree::CondExpr::Condition(lhs, op, rhs) => {
-                let lhs = (*lhs.deref()).emit(emitter);
-                let rhs = (*rhs.deref()).emit(emitter);
+                let (lhs, rhs, signed) = emit_operands(lhs, rhs, emitter);
+                let pick = |s, u| if signed { s } else { u };
                 match op {
                     tree::JudgeOperator::GT => match lhs {
                         BasicValueEnum::IntValue(lhs) => emitter.builder
-                            .build_int_compare(inkwell::IntPredicate::SGT, lhs, rhs.into_int_value(), "gttmp").unwrap().as_basic_value_enum(),
+                            .build_int_compare(pick(inkwell::IntPredicate::SGT, inkwell::IntPredicate::UGT), lhs, rhs.into_int_value(), "gttmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::OGT, lhs, rhs.into_float_value(), "gttmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
                     }
                     tree::JudgeOperator::GE => match lhs {
                         BasicValueEnum::IntValue(lhs) => emitter.builder
-                            .build_int_compare(inkwell::IntPredicate::SGE, lhs, rhs.into_int_value(), "getmp").unwrap().as_basic_value_enum(),
+                            .build_int_compare(pick(inkwell::IntPredicate::SGE, inkwell::IntPredicate::UGE), lhs, rhs.into_int_value(), "getmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::OGE, lhs, rhs.into_float_value(), "getmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
                     }
                     tree::JudgeOperator::LT => match lhs {
                         BasicValueEnum::IntValue(lhs) => emitter.builder
-                            .build_int_compare(inkwell::IntPredicate::SLT, lhs, rhs.into_int_value(), "lttmp").unwrap().as_basic_value_enum(),
+                            .build_int_compare(pick(inkwell::IntPredicate::SLT, inkwell::IntPredicate::ULT), lhs, rhs.into_int_value(), "lttmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::OLT, lhs, rhs.into_float_value(), "lttmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
                     }
                     tree::JudgeOperator::LE => match lhs {
                         BasicValueEnum::IntValue(lhs) => emitter.builder
-                            .build_int_compare(inkwell::IntPredicate::SLE, lhs, rhs.into_int_value(), "letmp").unwrap().as_basic_value_enum(),
+                            .build_int_compare(pick(inkwell::IntPredicate::SLE, inkwell::IntPredicate::ULE), lhs, rhs.into_int_value(), "letmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::OLE, lhs, rhs.into_float_value(), "letmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
                     }
                     tree::JudgeOperator::EQ => match lhs {
+                        BasicValueEnum::StructValue(_) => emitter.emit_value_eq(lhs, rhs).as_basic_value_enum(),
                         BasicValueEnum::IntValue(lhs) => emitter.builder
                             .build_int_compare(inkwell::IntPredicate::EQ, lhs, rhs.into_int_value(), "eqtmp").unwrap().as_basic_value_enum(),
+                        BasicValueEnum::PointerValue(lhs) => emitter.builder
+                            .build_int_compare(inkwell::IntPredicate::EQ, lhs, rhs.into_pointer_value(), "eqtmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::OEQ, lhs, rhs.into_float_value(), "eqtmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
                     }
                     tree::JudgeOperator::NE => match lhs {
+                        BasicValueEnum::StructValue(_) => {
+                            let eq = emitter.emit_value_eq(lhs, rhs);
+                            emitter.builder.build_not(eq, "netmp").unwrap().as_basic_value_enum()
+                        }
                         BasicValueEnum::IntValue(lhs) => emitter.builder
                             .build_int_compare(inkwell::IntPredicate::NE, lhs, rhs.into_int_value(), "netmp").unwrap().as_basic_value_enum(),
+                        BasicValueEnum::PointerValue(lhs) => emitter.builder
+                            .build_int_compare(inkwell::IntPredicate::NE, lhs, rhs.into_pointer_value(), "netmp").unwrap().as_basic_value_enum(),
                         BasicValueEnum::FloatValue(lhs) => emitter.builder
                             .build_float_compare(inkwell::FloatPredicate::ONE, lhs, rhs.into_float_value(), "netmp").unwrap().as_basic_value_enum(),
                         _ => panic!("Error in CondExpr"),
@@ -724,9 +1083,14 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Value {
     {
         match self {
             tree::Value::Integer(n) => Some(emitter.context.i32_type().const_int(*n as u64, false).as_basic_value_enum()),
+            tree::Value::SizedInteger(n, t) => Some(emitter.context.custom_width_int_type(t.bits).const_int(*n, false).as_basic_value_enum()),
             tree::Value::Char(c) => Some(emitter.context.i8_type().const_int(*c as u64, false).as_basic_value_enum()),
-            tree::Value::Float(f) => Some(emitter.context.f32_type().const_float(*f as f64).as_basic_value_enum()),
-            tree::Value::String(s) => Some(emitter.emit_global_string(&mut s.to_owned(), "").as_basic_value_enum()),
+            tree::Value::Float(f) => Some(emitter.context.f32_type().const_float(*f).as_basic_value_enum()),
+            tree::Value::Double(d) => Some(emitter.context.f64_type().const_float(*d).as_basic_value_enum()),
+            tree::Value::String(s) => {
+                let literal = emitter.emit_global_string(&mut s.to_owned(), "");
+                emitter.emit_runtime_call("spl_str_new", &[literal.into()])
+            }
             tree::Value::Pointer(_) => Some(emitter.context.ptr_type(AddressSpace::default()).const_null().as_basic_value_enum()),
             tree::Value::Null => None,
             _ => panic!("Error in Value"),
@@ -734,9 +1098,384 @@ impl<'ast, 'ctx> Emit<'ast, 'ctx> for tree::Value {
     }
 }
 
-fn get_array_dims<'ast, 'ctx>(dims: &'ast Vec<tree::CompExpr>, emitter: &mut Azuki<'ast, 'ctx>) -> Vec<IntValue<'ctx>>
+// Array sizes are constants checked by the analyser, folded the same way here
+fn get_array_dims<'ast, 'ctx>(dims: &'ast Vec<tree::CompExpr>, emitter: &Azuki<'ast, 'ctx>) -> Vec<IntValue<'ctx>> {
+    dims.deref().iter().rev().map(|dim| match dim.eval_const(&|name| emitter.get_const(name)) {
+        Some(ConstValue::Int(n)) => emitter.context.i32_type().const_int(n as u64, false),
+        _ => panic!("Array size is not a constant"),
+    }).collect::<Vec<IntValue>>()
+}
+
+// Literals can be the initial value of a global directly, strings need the builder
+fn is_const_initializer(expr: &tree::CompExpr) -> bool {
+    match expr {
+        tree::CompExpr::Value(val) => !matches!(val, tree::Value::String(_)),
+        tree::CompExpr::InitList(items) => items.iter().all(is_const_initializer),
+        tree::CompExpr::Designated(_, val) => is_const_initializer(val),
+        _ => false,
+    }
+}
+
+// Type of a declared variable, an element type wrapped in its array dimensions
+fn get_declared_type<'ast, 'ctx>(ty: &tree::Value, dims: &'ast Vec<tree::CompExpr>, emitter: &mut Azuki<'ast, 'ctx>) -> BasicTypeEnum<'ctx>
+    where 'ast: 'ctx
+{
+    let elem_ty = emitter.get_llvm_type(ty).expect("Variables cannot be void");
+    get_array_dims(dims, emitter).iter().fold(elem_ty,
+        |acc, len| acc.array_type(len.get_zero_extended_constant().unwrap() as u32).as_basic_type_enum()
+    )
+}
+
+// Flatten a braced initializer into (index path, item) pairs the way C does: nested
+// braces may be elided, `.field = v` selects a struct field and an item of the
+// element's own struct type initializes the element as a whole
+fn collect_initializer<'ast, 'ctx>(
+    ty: BasicTypeEnum<'ctx>,
+    items: &'ast [tree::CompExpr],
+    pos: &mut usize,
+    braced: bool,
+    path: &mut Vec<u32>,
+    leaves: &mut Vec<(Vec<u32>, &'ast tree::CompExpr)>,
+    emitter: &Azuki<'ast, 'ctx>,
+) {
+    let len = match ty {
+        BasicTypeEnum::ArrayType(t) => t.len(),
+        BasicTypeEnum::StructType(t) => t.count_fields(),
+        _ => {
+            if let Some(item) = items.get(*pos) {
+                leaves.push((path.clone(), item));
+                *pos += 1;
+            }
+            return;
+        }
+    };
+
+    let mut cursor = 0;
+    while *pos < items.len() {
+        match &items[*pos] {
+            tree::CompExpr::Designated(field, value) => {
+                // A designator belongs to the innermost braced struct
+                if !braced {
+                    break;
+                }
+                let BasicTypeEnum::StructType(struct_ty) = ty else { panic!("Designator outside of a struct") };
+                let fields = emitter.struct_fields.get(struct_ty.get_name().unwrap().to_str().unwrap()).unwrap();
+                cursor = fields.get(field.as_str()).unwrap().0 as u32;
+                *pos += 1;
+                path.push(cursor);
+                match value.as_ref() {
+                    tree::CompExpr::InitList(sub) => collect_initializer(get_element_type(ty, cursor), sub, &mut 0, true, path, leaves, emitter),
+                    value => collect_initializer(get_element_type(ty, cursor), std::slice::from_ref(value), &mut 0, false, path, leaves, emitter),
+                }
+                path.pop();
+            }
+            // Excess items belong to the enclosing aggregate
+            _ if cursor >= len => break,
+            tree::CompExpr::InitList(sub) => {
+                *pos += 1;
+                path.push(cursor);
+                collect_initializer(get_element_type(ty, cursor), sub, &mut 0, true, path, leaves, emitter);
+                path.pop();
+            }
+            item => {
+                let elem_ty = get_element_type(ty, cursor);
+                let whole = match (elem_ty, get_expr_type(item, emitter)) {
+                    (BasicTypeEnum::StructType(t), Some(tree::Value::Struct(name))) =>
+                        t.get_name().is_some_and(|n| n.to_str().unwrap() == name),
+                    (BasicTypeEnum::ArrayType(_) | BasicTypeEnum::StructType(_), _) => false,
+                    _ => true,
+                };
+                path.push(cursor);
+                if whole {
+                    leaves.push((path.clone(), item));
+                    *pos += 1;
+                } else {
+                    collect_initializer(elem_ty, items, pos, false, path, leaves, emitter);
+                }
+                path.pop();
+            }
+        }
+        cursor += 1;
+    }
+}
+
+fn get_element_type(ty: BasicTypeEnum, index: u32) -> BasicTypeEnum {
+    match ty {
+        BasicTypeEnum::ArrayType(t) => t.get_element_type(),
+        BasicTypeEnum::StructType(t) => t.get_field_type_at_index(index).unwrap(),
+        _ => panic!("Not an aggregate type"),
+    }
+}
+
+// Number of scalars stored in a value of the type
+fn count_scalars(ty: BasicTypeEnum) -> usize {
+    match ty {
+        BasicTypeEnum::ArrayType(t) => t.len() as usize * count_scalars(t.get_element_type()),
+        BasicTypeEnum::StructType(t) => t.get_field_types().into_iter().map(count_scalars).sum(),
+        _ => 1,
+    }
+}
+
+// Store a braced initializer into a local, whatever is left out is zero
+fn emit_initializer<'ast, 'ctx>(ptr: PointerValue<'ctx>, ty: BasicTypeEnum<'ctx>, items: &'ast [tree::CompExpr], emitter: &mut Azuki<'ast, 'ctx>)
+    where 'ast: 'ctx
+{
+    let mut leaves = Vec::new();
+    collect_initializer(ty, items, &mut 0, true, &mut Vec::new(), &mut leaves, emitter);
+
+    let covered: usize = leaves.iter().map(|(path, _)| path).collect::<HashSet<_>>().into_iter()
+        .map(|path| count_scalars(path.iter().fold(ty, |acc, i| get_element_type(acc, *i))))
+        .sum();
+    if covered < count_scalars(ty) {
+        emitter.builder.build_store(ptr, ty.const_zero()).expect("Store failed");
+    }
+
+    let i32_type = emitter.context.i32_type();
+    for (path, item) in leaves {
+        let elem_ty = path.iter().fold(ty, |acc, i| get_element_type(acc, *i));
+        let elem_ptr = if path.is_empty() {
+            ptr
+        } else {
+            let mut idx_vals = vec![i32_type.const_zero()];
+            idx_vals.extend(path.iter().map(|i| i32_type.const_int(*i as u64, false)));
+            unsafe { emitter.builder.build_gep(ty, ptr, idx_vals.as_slice(), "index").unwrap() }
+        };
+        match elem_ty {
+            BasicTypeEnum::StructType(struct_ty) => {
+                let src = emit_struct_ptr(item, emitter);
+                emitter.emit_struct_copy(elem_ptr, src, struct_ty, false);
+            }
+            _ => emit_store(elem_ptr, item, elem_ty, emitter),
+        }
+    }
+}
+
+// Constant value of a braced initializer of a global, zero filled
+fn emit_const_initializer<'ast, 'ctx>(ty: BasicTypeEnum<'ctx>, items: &'ast [tree::CompExpr], emitter: &mut Azuki<'ast, 'ctx>) -> BasicValueEnum<'ctx>
+    where 'ast: 'ctx
+{
+    let mut leaves = Vec::new();
+    collect_initializer(ty, items, &mut 0, true, &mut Vec::new(), &mut leaves, emitter);
+    let values = leaves.into_iter()
+        .map(|(path, item)| {
+            let elem_ty = path.iter().fold(ty, |acc, i| get_element_type(acc, *i));
+            (path, emit_coerced(item, elem_ty, emitter))
+        })
+        .collect::<HashMap<Vec<u32>, BasicValueEnum>>();
+    build_const(ty, &mut Vec::new(), &values)
+}
+
+fn build_const<'ctx>(ty: BasicTypeEnum<'ctx>, path: &mut Vec<u32>, values: &HashMap<Vec<u32>, BasicValueEnum<'ctx>>) -> BasicValueEnum<'ctx> {
+    if let Some(val) = values.get(path) {
+        return *val;
+    }
+    let len = match ty {
+        BasicTypeEnum::ArrayType(t) => t.len(),
+        BasicTypeEnum::StructType(t) => t.count_fields(),
+        _ => return ty.const_zero(),
+    };
+    let elems = (0..len).map(|i| {
+        path.push(i);
+        let elem = build_const(get_element_type(ty, i), path, values);
+        path.pop();
+        elem
+    }).collect::<Vec<BasicValueEnum>>();
+
+    match ty {
+        BasicTypeEnum::StructType(t) => t.const_named_struct(&elems).as_basic_value_enum(),
+        _ => match get_element_type(ty, 0) {
+            BasicTypeEnum::IntType(t) => t.const_array(&elems.iter().map(|v| v.into_int_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::FloatType(t) => t.const_array(&elems.iter().map(|v| v.into_float_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::PointerType(t) => t.const_array(&elems.iter().map(|v| v.into_pointer_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::ArrayType(t) => t.const_array(&elems.iter().map(|v| v.into_array_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::StructType(t) => t.const_array(&elems.iter().map(|v| v.into_struct_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::VectorType(_) => panic!("Not support type of array"),
+        }.as_basic_value_enum(),
+    }
+}
+
+// Address of a struct valued expression, temporaries are spilled to the stack
+fn emit_struct_ptr<'ast, 'ctx>(expr: &'ast tree::CompExpr, emitter: &mut Azuki<'ast, 'ctx>) -> PointerValue<'ctx>
+    where 'ast: 'ctx
+{
+    match expr {
+        tree::CompExpr::Variable(var) => var.emit(emitter).unwrap().0.into_pointer_value(),
+        _ => {
+            let val = expr.emit(emitter);
+            let tmp = emitter.builder.build_alloca(val.get_type(), "tmp").unwrap();
+            emitter.builder.build_store(tmp, val).expect("Store failed");
+            tmp
+        }
+    }
+}
+
+// Store an expression into memory of the given type, strings are
+// handed to the runtime library which keeps count of their references
+fn emit_store<'ast, 'ctx>(ptr: PointerValue<'ctx>, expr: &'ast tree::CompExpr, ty: BasicTypeEnum<'ctx>, emitter: &mut Azuki<'ast, 'ctx>)
+    where 'ast: 'ctx
+{
+    let val = emit_coerced(expr, ty, emitter);
+    if is_string(expr, emitter) {
+        emitter.emit_string_store(ptr, val);
+    } else {
+        emitter.builder.build_store(ptr, val).expect("Store failed");
+    }
+}
+
+fn is_string<'ast, 'ctx>(expr: &'ast tree::CompExpr, emitter: &Azuki<'ast, 'ctx>) -> bool {
+    matches!(get_expr_type(expr, emitter), Some(tree::Value::String(_)))
+}
+
+// Whether a reference indexes into a string rather than an array,
+// that is it has more indices than its variable has array dimensions
+fn is_string_index<'ast, 'ctx>(var: &'ast tree::Variable, emitter: &Azuki<'ast, 'ctx>) -> bool {
+    let tree::Variable::VarReference(name, dims) = var else { return false };
+    let Some((_, mut ty)) = emitter.get_var(name) else { return false };
+    let mut levels = 0;
+    while let BasicTypeEnum::ArrayType(t) = ty {
+        ty = t.get_element_type();
+        levels += 1;
+    }
+    dims.len() > levels && matches!(emitter.get_var_type(name), Some(tree::Value::String(_)))
+}
+
+// Integer type of a value in arithmetic, characters are promoted to int
+fn int_type_of(ty: &tree::Value) -> Option<tree::IntType> {
+    match ty {
+        tree::Value::Integer(_) | tree::Value::Char(_) => Some(tree::IntType::INT),
+        tree::Value::SizedInteger(_, t) => Some(*t),
+        _ => None,
+    }
+}
+
+// Whether an integer expression is extended as signed, LLVM types carry no sign
+fn is_signed<'ast, 'ctx>(expr: &'ast tree::CompExpr, emitter: &Azuki<'ast, 'ctx>) -> bool {
+    get_expr_type(expr, emitter).and_then(|t| int_type_of(&t)).is_none_or(|t| t.signed)
+}
+
+// Convert the emitted value of an expression to the type it is stored into.
+// Constant floating point expressions are folded again at the target type,
+// so a literal assigned to a double keeps its full precision
+fn coerce<'ast, 'ctx>(expr: &'ast tree::CompExpr, val: BasicValueEnum<'ctx>, ty: BasicTypeEnum<'ctx>, emitter: &Azuki<'ast, 'ctx>) -> BasicValueEnum<'ctx> {
+    if let BasicTypeEnum::FloatType(t) = ty {
+        if let Some(ConstValue::Float(f)) = expr.eval_const(&|name| emitter.get_const(name)) {
+            return t.const_float(f).as_basic_value_enum();
+        }
+    }
+    emitter.emit_cast(val, ty, is_signed(expr, emitter))
+}
+
+fn emit_coerced<'ast, 'ctx>(expr: &'ast tree::CompExpr, ty: BasicTypeEnum<'ctx>, emitter: &mut Azuki<'ast, 'ctx>) -> BasicValueEnum<'ctx>
+    where 'ast: 'ctx
+{
+    let val = expr.emit(emitter);
+    coerce(expr, val, ty, emitter)
+}
+
+// Type two numeric operands meet at: integers widen to their common type,
+// a float meeting a double becomes a double
+fn common_type(ltype: &tree::Value, rtype: &tree::Value) -> Option<tree::Value> {
+    match (ltype, rtype) {
+        (tree::Value::Float(_), tree::Value::Float(_)) => Some(tree::Value::Float(0.0)),
+        (tree::Value::Float(_) | tree::Value::Double(_), tree::Value::Float(_) | tree::Value::Double(_)) => Some(tree::Value::Double(0.0)),
+        _ => match int_type_of(ltype)?.common(int_type_of(rtype)?) {
+            tree::IntType::INT => Some(tree::Value::Integer(0)),
+            t => Some(tree::Value::SizedInteger(0, t)),
+        },
+    }
+}
+
+// Emit both operands of a binary operation converted to their common type,
+// whose signedness picks the division and comparison to use
+fn emit_operands<'ast, 'ctx>(lhs: &'ast tree::CompExpr, rhs: &'ast tree::CompExpr, emitter: &mut Azuki<'ast, 'ctx>) -> (BasicValueEnum<'ctx>, BasicValueEnum<'ctx>, bool)
     where 'ast: 'ctx
 {
-    dims.deref().iter().rev().map(|dim|
-        dim.emit(emitter).into_int_value()).collect::<Vec<IntValue>>()
+    let common = match (get_expr_type(lhs, emitter), get_expr_type(rhs, emitter)) {
+        (Some(l), Some(r)) => common_type(&l, &r),
+        _ => None,
+    };
+    let signed = common.as_ref().and_then(int_type_of).is_none_or(|t| t.signed);
+    match common.and_then(|t| emitter.get_llvm_type(&t)) {
+        Some(ty) => (emit_coerced(lhs, ty, emitter), emit_coerced(rhs, ty, emitter), signed),
+        None => (lhs.emit(emitter), rhs.emit(emitter), signed),
+    }
+}
+
+// Recover the source-level type of an expression, pointers are opaque in LLVM
+// so the pointee type has to be tracked through the AST
+fn get_expr_type<'ast, 'ctx>(expr: &'ast tree::CompExpr, emitter: &Azuki<'ast, 'ctx>) -> Option<tree::Value> {
+    match expr {
+        tree::CompExpr::Value(val) => Some(val.clone()),
+        tree::CompExpr::Variable(var) => get_var_type(var, emitter),
+        tree::CompExpr::FuncCall(tree::Function::FuncReference(name, ..)) => emitter.ret_types.get(name.as_str()).cloned(),
+        tree::CompExpr::UnaryOperation(tree::UnaryOperator::Ref, expr) =>
+            get_expr_type(expr, emitter).map(|t| tree::Value::Pointer(Box::new(t))),
+        tree::CompExpr::UnaryOperation(tree::UnaryOperator::Deref, expr) => get_pointee_type(expr, emitter),
+        tree::CompExpr::New(ty, _) => Some(tree::Value::Pointer(ty.clone())),
+        tree::CompExpr::SizeOf(_) => Some(tree::Value::Integer(0)),
+        tree::CompExpr::BinaryOperation(lhs, _, rhs) => {
+            match (get_expr_type(lhs, emitter)?, get_expr_type(rhs, emitter)?) {
+                (tree::Value::Pointer(_), tree::Value::Pointer(_)) => Some(tree::Value::Integer(0)),
+                (ty @ tree::Value::Pointer(_), _) | (_, ty @ tree::Value::Pointer(_)) => Some(ty),
+                (l, r) => Some(common_type(&l, &r).unwrap_or(l)),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn get_var_type<'ast, 'ctx>(var: &'ast tree::Variable, emitter: &Azuki<'ast, 'ctx>) -> Option<tree::Value> {
+    match var {
+        tree::Variable::VarReference(..) if is_string_index(var, emitter) => Some(tree::Value::Char('\0')),
+        tree::Variable::VarReference(name, _) => emitter.get_var_type(name),
+        tree::Variable::Dereference(expr) => get_pointee_type(expr, emitter),
+        tree::Variable::StructReference(vars) => {
+            let mut vars = vars.iter();
+            let mut ty = get_var_type(vars.next()?, emitter)?;
+            for member in vars {
+                let tree::Value::Struct(struct_name) = ty else { return None };
+                let fields = emitter.struct_fields.get(struct_name.as_str())?;
+                ty = fields.get(member.get_name().as_str())?.1.clone();
+            }
+            Some(ty)
+        }
+        _ => None,
+    }
+}
+
+// Type of a reference that names a whole array, or part of one, e.g. `m[1]`
+// of `int m[3][4]`, found without emitting it
+fn get_array_type<'ast, 'ctx>(var: &'ast tree::Variable, emitter: &Azuki<'ast, 'ctx>) -> Option<ArrayType<'ctx>> {
+    // Indices past the array levels index a string
+    let strip = |ty: BasicTypeEnum<'ctx>, dims: &[tree::CompExpr]| dims.iter().try_fold(ty, |ty, _| match ty {
+        BasicTypeEnum::ArrayType(t) => Some(t.get_element_type()),
+        _ => None,
+    });
+    let ty = match var {
+        tree::Variable::VarReference(name, dims) => strip(emitter.get_var(name)?.1, dims)?,
+        tree::Variable::StructReference(vars) => {
+            let tree::Variable::VarReference(name, dims) = vars.first()? else { return None };
+            let mut ty = strip(emitter.get_var(name)?.1, dims)?;
+            for member in &vars[1..] {
+                let tree::Variable::VarReference(name, dims) = member else { return None };
+                let BasicTypeEnum::StructType(struct_ty) = ty else { return None };
+                let fields = emitter.struct_fields.get(struct_ty.get_name()?.to_str().ok()?)?;
+                let (index, _) = fields.get(name.as_str())?;
+                ty = strip(struct_ty.get_field_type_at_index(*index as u32)?, dims)?;
+            }
+            ty
+        }
+        _ => return None,
+    };
+    match ty {
+        BasicTypeEnum::ArrayType(t) => Some(t),
+        _ => None,
+    }
+}
+
+fn get_pointee_type<'ast, 'ctx>(expr: &'ast tree::CompExpr, emitter: &Azuki<'ast, 'ctx>) -> Option<tree::Value> {
+    match get_expr_type(expr, emitter)? {
+        tree::Value::Pointer(pointee) => Some(*pointee),
+        _ => None,
+    }
 }
@@ -44,6 +44,7 @@ mod tests {
     use std::io::Read;
     #[allow(unused_imports)]
     use crate::{emit_llvmir, emit_llvmir_to_file, emit_object};
+    use spl_analyser::walker::Walker;
 
     fn test_from_file(source_path: &str, expected_path: &str, ir: bool, print_ast: bool) {
         let mut source = String::new();
@@ -62,6 +63,20 @@ mod tests {
         assert_eq!(res, expected);
     }
 
+    // Overloads and instances of generic functions are named by the analyser,
+    // the code generator is handed the program it checked
+    fn test_typed_from_file(source_path: &str, expected_path: &str) {
+        let mut source = String::new();
+        let mut expected = String::new();
+        File::open(source_path).unwrap().read_to_string(&mut source).unwrap();
+        File::open(expected_path).unwrap().read_to_string(&mut expected).unwrap();
+        let mut walker = Walker::new(spl_parser::parse(&source).unwrap(), source_path, false);
+        walker.traverse();
+        assert_eq!(walker.print_errors(), Ok(()));
+        let res = emit_llvmir(source_path.split("/").last().unwrap(), walker.typed_program());
+        assert_eq!(res, expected);
+    }
+
     #[test]
     fn test_compexpr() {
         let source = "int main() { return 1 + 2 * 3; } ";
@@ -113,6 +128,14 @@ mod tests {
         assert_eq!(ir, "; ModuleID = 'test_forexpr.spl'\nsource_filename = \"test_forexpr.spl\"\n\n@0 = internal global [4 x i8] c\"%d\\0A\\00\"\n\ndefine i32 @main() {\nentry:\n  %a = alloca i32, align 4\n  store i32 1, ptr %a, align 4\n  br label %init\n\ninit:                                             ; preds = %entry\n  %i = alloca i32, align 4\n  store i32 0, ptr %i, align 4\n  br label %cond\n\ncond:                                             ; preds = %step, %init\n  %i1 = load i32, ptr %i, align 4\n  %lttmp = icmp slt i32 %i1, 10\n  br i1 %lttmp, label %body, label %merge\n\nbody:                                             ; preds = %cond\n  %a2 = load i32, ptr %a, align 4\n  %addtmp = add i32 %a2, 1\n  store i32 %addtmp, ptr %a, align 4\n  %a3 = load i32, ptr %a, align 4\n  %0 = call i32 (ptr, ...) @printf(ptr @0, i32 %a3)\n  %a4 = load i32, ptr %a, align 4\n  %eqtmp = icmp eq i32 %a4, 4\n  br i1 %eqtmp, label %then, label %merge5\n\nstep:                                             ; preds = %merge5\n  %i6 = load i32, ptr %i, align 4\n  %addtmp7 = add i32 %i6, 1\n  store i32 %addtmp7, ptr %i, align 4\n  br label %cond\n\nmerge:                                            ; preds = %then, %cond\n  %a8 = load i32, ptr %a, align 4\n  ret i32 %a8\n\nthen:                                             ; preds = %body\n  br label %merge\n\nmerge5:                                           ; preds = %body\n  br label %step\n}\n\ndeclare i32 @printf(ptr, ...)\n");
     }
 
+    #[test]
+    fn test_labeled_loop() {
+        let source = "int main() { int a = 1; outer: while (a < 10) { while (a < 5) { a = a + 1; continue outer; } break outer; } return a; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_labeled_loop.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_labeled_loop.spl'\nsource_filename = \"test_labeled_loop.spl\"\n\ndefine i32 @main() {\nentry:\n  %a = alloca i32, align 4\n  store i32 1, ptr %a, align 4\n  br label %cond\n\ncond:                                             ; preds = %body3, %entry\n  %a1 = load i32, ptr %a, align 4\n  %lttmp = icmp slt i32 %a1, 10\n  br i1 %lttmp, label %body, label %merge\n\nbody:                                             ; preds = %cond\n  br label %cond2\n\nmerge:                                            ; preds = %merge4, %cond\n  %a8 = load i32, ptr %a, align 4\n  ret i32 %a8\n\ncond2:                                            ; preds = %body\n  %a5 = load i32, ptr %a, align 4\n  %lttmp6 = icmp slt i32 %a5, 5\n  br i1 %lttmp6, label %body3, label %merge4\n\nbody3:                                            ; preds = %cond2\n  %a7 = load i32, ptr %a, align 4\n  %addtmp = add i32 %a7, 1\n  store i32 %addtmp, ptr %a, align 4\n  br label %cond\n\nmerge4:                                           ; preds = %cond2\n  br label %merge\n}\n");
+    }
+
     #[test]
     fn test_global_variable() {
         let source = "int a[2][3] = {0, 1, 2, 3, 4, 2}; int main() { printf(\"%d\\n\", a[1][2]); return 0; }";
@@ -132,6 +155,71 @@ mod tests {
         assert_eq!(ir, "; ModuleID = 'test_struct_decl.spl'\nsource_filename = \"test_struct_decl.spl\"\n\n%Fruit = type { i32, [3 x float] }\n\n@0 = internal global [4 x i8] c\"%d\\0A\\00\"\n\ndefine i32 @main() {\nentry:\n  %apple = alloca %Fruit, align 8\n  %weight = getelementptr inbounds %Fruit, ptr %apple, i32 0, i32 0\n  store i32 100, ptr %weight, align 4\n  %weight1 = getelementptr inbounds %Fruit, ptr %apple, i32 0, i32 0\n  %apple.weight = load i32, ptr %weight1, align 4\n  %0 = call i32 (ptr, ...) @printf(ptr @0, i32 %apple.weight)\n  ret i32 0\n}\n\ndeclare i32 @printf(ptr, ...)\n");
     }
 
+    #[test]
+    fn test_void_function() {
+        // Void functions return when they reach the end of their body
+        let source = "void show(int n) { printf(\"%d\\n\", n); } int main() { show(1); return 0; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_void_function.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_void_function.spl'\nsource_filename = \"test_void_function.spl\"\n\n@0 = internal global [4 x i8] c\"%d\\0A\\00\"\n\ndefine void @show(i32 %n) {\nentry:\n  %n1 = alloca i32, align 4\n  store i32 %n, ptr %n1, align 4\n  %n2 = load i32, ptr %n1, align 4\n  %0 = call i32 (ptr, ...) @printf(ptr @0, i32 %n2)\n  ret void\n}\n\ndeclare i32 @printf(ptr, ...)\n\ndefine i32 @main() {\nentry:\n  call void @show(i32 1)\n  ret i32 0\n}\n");
+    }
+
+    #[test]
+    fn test_struct_strings() {
+        // String fields are compared by their contents, copied with a reference of
+        // their own and released when the struct goes out of scope
+        let source = "struct Tag { int id; string name; }; int main() { struct Tag a; struct Tag b; a.name = \"x\"; b = a; if (a == b) { return 1; } return 0; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_struct_strings.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_struct_strings.spl'\nsource_filename = \"test_struct_strings.spl\"\n\n%Tag = type { i32, ptr }\n\n@0 = internal global [2 x i8] c\"x\\00\"\n\ndefine i32 @main() {\nentry:\n  %mark = call i64 @spl_str_mark()\n  %a = alloca %Tag, align 8\n  store %Tag zeroinitializer, ptr %a, align 8\n  %field = getelementptr inbounds %Tag, ptr %a, i32 0, i32 1\n  %b = alloca %Tag, align 8\n  store %Tag zeroinitializer, ptr %b, align 8\n  %field1 = getelementptr inbounds %Tag, ptr %b, i32 0, i32 1\n  %0 = call ptr @spl_str_new(ptr @0)\n  %name = getelementptr inbounds %Tag, ptr %a, i32 0, i32 1\n  call void @spl_str_assign(ptr %name, ptr %0)\n  call void @spl_str_drain(i64 %mark)\n  %field2 = getelementptr inbounds %Tag, ptr %a, i32 0, i32 1\n  call void @spl_str_retain_fields(ptr %field2, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  %field3 = getelementptr inbounds %Tag, ptr %b, i32 0, i32 1\n  call void @spl_str_release_fields(ptr %field3, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %b, ptr align 1 %a, i64 ptrtoint (ptr getelementptr (%Tag, ptr null, i32 1) to i64), i1 false)\n  %a4 = load %Tag, ptr %a, align 8\n  %b5 = load %Tag, ptr %b, align 8\n  %lfield = extractvalue %Tag %a4, 0\n  %rfield = extractvalue %Tag %b5, 0\n  %lfield6 = extractvalue %Tag %a4, 1\n  %rfield7 = extractvalue %Tag %b5, 1\n  %eqtmp = icmp eq i32 %lfield, %rfield\n  %eqtmp8 = and i1 true, %eqtmp\n  %1 = call i32 @spl_str_cmp(ptr %lfield6, ptr %rfield7)\n  %eqtmp9 = icmp eq i32 %1, 0\n  %eqtmp10 = and i1 %eqtmp8, %eqtmp9\n  br i1 %eqtmp10, label %then, label %merge\n\nthen:                                             ; preds = %entry\n  call void @spl_str_release_fields(ptr %field, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %field1, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  ret i32 1\n\nmerge:                                            ; preds = %entry\n  call void @spl_str_release_fields(ptr %field, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %field1, i64 1, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  ret i32 0\n}\n\ndeclare ptr @spl_str_new(ptr)\n\ndeclare void @spl_str_assign(ptr, ptr)\n\ndeclare i64 @spl_str_mark()\n\ndeclare void @spl_str_drain(i64)\n\ndeclare void @spl_str_retain_fields(ptr, i64, i64)\n\ndeclare void @spl_str_release_fields(ptr, i64, i64)\n\n; Function Attrs: nocallback nofree nounwind willreturn memory(argmem: readwrite)\ndeclare void @llvm.memcpy.p0.p0.i64(ptr noalias nocapture writeonly, ptr noalias nocapture readonly, i64, i1 immarg) #0\n\ndeclare i32 @spl_str_cmp(ptr, ptr)\n\nattributes #0 = { nocallback nofree nounwind willreturn memory(argmem: readwrite) }\n");
+    }
+
+    #[test]
+    fn test_pointer() {
+        let source = "struct Node { int value; struct Node *next; }; int main() { int a[3]; int *p; int **pp; struct Node n; struct Node *q; p = &a[0]; pp = &p; *(*pp + 2) = 7; q = &n; q->next = q; q->value = *(p + 2) - *p; if (q->next != null) { return q->next->value; } return 0; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_pointer.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_pointer.spl'\nsource_filename = \"test_pointer.spl\"\n\n%Node = type { i32, ptr }\n\ndefine i32 @main() {\nentry:\n  %a = alloca [3 x i32], align 4\n  %p = alloca ptr, align 8\n  %pp = alloca ptr, align 8\n  %n = alloca %Node, align 8\n  %q = alloca ptr, align 8\n  %index = getelementptr inbounds [3 x i32], ptr %a, i32 0, i32 0\n  store ptr %index, ptr %p, align 8\n  store ptr %p, ptr %pp, align 8\n  %pp1 = load ptr, ptr %pp, align 8\n  %deref = load ptr, ptr %pp1, align 8\n  %addtmp = getelementptr inbounds i32, ptr %deref, i32 2\n  store i32 7, ptr %addtmp, align 4\n  store ptr %n, ptr %q, align 8\n  %q2 = load ptr, ptr %q, align 8\n  %q3 = load ptr, ptr %q, align 8\n  %next = getelementptr inbounds %Node, ptr %q3, i32 0, i32 1\n  store ptr %q2, ptr %next, align 8\n  %p4 = load ptr, ptr %p, align 8\n  %addtmp5 = getelementptr inbounds i32, ptr %p4, i32 2\n  %deref6 = load i32, ptr %addtmp5, align 4\n  %p7 = load ptr, ptr %p, align 8\n  %deref8 = load i32, ptr %p7, align 4\n  %subtmp = sub i32 %deref6, %deref8\n  %q9 = load ptr, ptr %q, align 8\n  %value = getelementptr inbounds %Node, ptr %q9, i32 0, i32 0\n  store i32 %subtmp, ptr %value, align 4\n  %q10 = load ptr, ptr %q, align 8\n  %next11 = getelementptr inbounds %Node, ptr %q10, i32 0, i32 1\n  %q.next = load ptr, ptr %next11, align 8\n  %netmp = icmp ne ptr %q.next, null\n  br i1 %netmp, label %then, label %merge\n\nthen:                                             ; preds = %entry\n  %q12 = load ptr, ptr %q, align 8\n  %next13 = getelementptr inbounds %Node, ptr %q12, i32 0, i32 1\n  %q.next14 = load ptr, ptr %next13, align 8\n  %value15 = getelementptr inbounds %Node, ptr %q.next14, i32 0, i32 0\n  %q.next.value = load i32, ptr %value15, align 4\n  ret i32 %q.next.value\n\nmerge:                                            ; preds = %entry\n  ret i32 0\n}\n");
+    }
+
+    #[test]
+    fn test_struct_value() {
+        // Structs are passed byval, returned through sret and copied whole
+        let source = "struct P { int x; int y; }; struct P add(struct P a, struct P b) { struct P c; c.x = a.x + b.x; c.y = a.y + b.y; return c; } int main() { struct P a; struct P b; a.x = 1; a.y = 2; b = add(a, a); if (a == b) { return 1; } return b.y; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_struct_value.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_struct_value.spl'\nsource_filename = \"test_struct_value.spl\"\n\n%P = type { i32, i32 }\n\ndefine void @add(ptr sret(%P) %sret, ptr byval(%P) %a, ptr byval(%P) %b) {\nentry:\n  %c = alloca %P, align 8\n  %x = getelementptr inbounds %P, ptr %a, i32 0, i32 0\n  %a.x = load i32, ptr %x, align 4\n  %x1 = getelementptr inbounds %P, ptr %b, i32 0, i32 0\n  %b.x = load i32, ptr %x1, align 4\n  %addtmp = add i32 %a.x, %b.x\n  %x2 = getelementptr inbounds %P, ptr %c, i32 0, i32 0\n  store i32 %addtmp, ptr %x2, align 4\n  %y = getelementptr inbounds %P, ptr %a, i32 0, i32 1\n  %a.y = load i32, ptr %y, align 4\n  %y3 = getelementptr inbounds %P, ptr %b, i32 0, i32 1\n  %b.y = load i32, ptr %y3, align 4\n  %addtmp4 = add i32 %a.y, %b.y\n  %y5 = getelementptr inbounds %P, ptr %c, i32 0, i32 1\n  store i32 %addtmp4, ptr %y5, align 4\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %sret, ptr align 1 %c, i64 ptrtoint (ptr getelementptr (%P, ptr null, i32 1) to i64), i1 false)\n  ret void\n}\n\n; Function Attrs: nocallback nofree nounwind willreturn memory(argmem: readwrite)\ndeclare void @llvm.memcpy.p0.p0.i64(ptr noalias nocapture writeonly, ptr noalias nocapture readonly, i64, i1 immarg) #0\n\ndefine i32 @main() {\nentry:\n  %a = alloca %P, align 8\n  %b = alloca %P, align 8\n  %x = getelementptr inbounds %P, ptr %a, i32 0, i32 0\n  store i32 1, ptr %x, align 4\n  %y = getelementptr inbounds %P, ptr %a, i32 0, i32 1\n  store i32 2, ptr %y, align 4\n  %sret = alloca %P, align 8\n  call void @add(ptr sret(%P) %sret, ptr byval(%P) %a, ptr byval(%P) %a)\n  %add = load %P, ptr %sret, align 4\n  %tmp = alloca %P, align 8\n  store %P %add, ptr %tmp, align 4\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %b, ptr align 1 %tmp, i64 ptrtoint (ptr getelementptr (%P, ptr null, i32 1) to i64), i1 false)\n  %a1 = load %P, ptr %a, align 4\n  %b2 = load %P, ptr %b, align 4\n  %lfield = extractvalue %P %a1, 0\n  %rfield = extractvalue %P %b2, 0\n  %lfield3 = extractvalue %P %a1, 1\n  %rfield4 = extractvalue %P %b2, 1\n  %eqtmp = icmp eq i32 %lfield, %rfield\n  %eqtmp5 = and i1 true, %eqtmp\n  %eqtmp6 = icmp eq i32 %lfield3, %rfield4\n  %eqtmp7 = and i1 %eqtmp5, %eqtmp6\n  br i1 %eqtmp7, label %then, label %merge\n\nthen:                                             ; preds = %entry\n  ret i32 1\n\nmerge:                                            ; preds = %entry\n  %y8 = getelementptr inbounds %P, ptr %b, i32 0, i32 1\n  %b.y = load i32, ptr %y8, align 4\n  ret i32 %b.y\n}\n\nattributes #0 = { nocallback nofree nounwind willreturn memory(argmem: readwrite) }\n");
+    }
+
+    #[test]
+    fn test_global_ctor() {
+        // Non-constant initializers are evaluated by the module constructor in order
+        let source = "struct P { int x; int y; }; int seed() { return 4; } int a = 2; int b = seed() + a; struct P p = {a, 3}; int main() { return b + p.x; }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_global_ctor.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_global_ctor.spl'\nsource_filename = \"test_global_ctor.spl\"\n\n%P = type { i32, i32 }\n\n@a = global i32 2\n@b = global i32 0\n@p = global %P zeroinitializer\n@llvm.global_ctors = appending global [1 x { i32, ptr, ptr }] [{ i32, ptr, ptr } { i32 65535, ptr @__spl_global_init, ptr null }]\n\ndefine i32 @seed() {\nentry:\n  ret i32 4\n}\n\ndefine internal void @__spl_global_init() {\nentry:\n  %seed = call i32 @seed()\n  %a = load i32, ptr @a, align 4\n  %addtmp = add i32 %seed, %a\n  store i32 %addtmp, ptr @b, align 4\n  %a1 = load i32, ptr @a, align 4\n  store i32 %a1, ptr @p, align 4\n  store i32 3, ptr getelementptr inbounds (%P, ptr @p, i32 0, i32 1), align 4\n  ret void\n}\n\ndefine i32 @main() {\nentry:\n  %b = load i32, ptr @b, align 4\n  %p.x = load i32, ptr @p, align 4\n  %addtmp = add i32 %b, %p.x\n  ret i32 %addtmp\n}\n");
+    }
+
+    #[test]
+    fn test_string_lifetime() {
+        // Variables own their strings, temporaries are drained after each statement and
+        // returned strings are kept in the pool for the caller
+        let source = "string greet(string name) { string s = \"hi \" + name; return s; } int main() { string names[2]; names[0] = greet(\"a\"); printf(\"%s\\n\", names[0]); return len(names[0]); }";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_string_lifetime.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_string_lifetime.spl'\nsource_filename = \"test_string_lifetime.spl\"\n\n@0 = internal global [4 x i8] c\"hi \\00\"\n@1 = internal global [2 x i8] c\"a\\00\"\n@2 = internal global [4 x i8] c\"%s\\0A\\00\"\n\ndefine ptr @greet(ptr %name) {\nentry:\n  %mark = call i64 @spl_str_mark()\n  %name1 = alloca ptr, align 8\n  store ptr null, ptr %name1, align 8\n  call void @spl_str_assign(ptr %name1, ptr %name)\n  %s = alloca ptr, align 8\n  store ptr null, ptr %s, align 8\n  %0 = call ptr @spl_str_new(ptr @0)\n  %name2 = load ptr, ptr %name1, align 8\n  %1 = call ptr @spl_str_concat(ptr %0, ptr %name2)\n  call void @spl_str_assign(ptr %s, ptr %1)\n  call void @spl_str_drain(i64 %mark)\n  %s3 = load ptr, ptr %s, align 8\n  %2 = call ptr @spl_str_keep(ptr %s3)\n  call void @spl_str_release(ptr %s, i64 1)\n  call void @spl_str_release(ptr %name1, i64 1)\n  ret ptr %2\n}\n\ndeclare void @spl_str_assign(ptr, ptr)\n\ndeclare ptr @spl_str_new(ptr)\n\ndeclare ptr @spl_str_concat(ptr, ptr)\n\ndeclare i64 @spl_str_mark()\n\ndeclare void @spl_str_drain(i64)\n\ndeclare ptr @spl_str_keep(ptr)\n\ndeclare void @spl_str_release(ptr, i64)\n\ndefine i32 @main() {\nentry:\n  %mark = call i64 @spl_str_mark()\n  %names = alloca [2 x ptr], align 8\n  store [2 x ptr] zeroinitializer, ptr %names, align 8\n  %0 = call ptr @spl_str_new(ptr @1)\n  %greet = call ptr @greet(ptr %0)\n  %index = getelementptr inbounds [2 x ptr], ptr %names, i32 0, i32 0\n  call void @spl_str_assign(ptr %index, ptr %greet)\n  call void @spl_str_drain(i64 %mark)\n  %index1 = getelementptr inbounds [2 x ptr], ptr %names, i32 0, i32 0\n  %names2 = load ptr, ptr %index1, align 8\n  %1 = call ptr @spl_str_cstr(ptr %names2)\n  %2 = call i32 (ptr, ...) @printf(ptr @2, ptr %1)\n  %index3 = getelementptr inbounds [2 x ptr], ptr %names, i32 0, i32 0\n  %names4 = load ptr, ptr %index3, align 8\n  %len = call i32 @spl_str_len(ptr %names4)\n  call void @spl_str_release(ptr %names, i64 2)\n  ret i32 %len\n}\n\ndeclare ptr @spl_str_cstr(ptr)\n\ndeclare i32 @printf(ptr, ...)\n\ndeclare i32 @spl_str_len(ptr)\n");
+    }
+
+    #[test]
+    fn test_array_copy() {
+        // Whole arrays are copied with memcpy, the copy takes its own references to
+        // the strings and gives up those it held, in arrays of structs and struct fields too
+        let source = "struct pair {\n    string key;\n    int n;\n};\n\nstruct table {\n    string names[2];\n};\n\nint main() {\n    string a[2];\n    string b[2];\n    struct pair p[2];\n    struct pair q[2];\n    int x[3];\n    int y[3];\n    a[0] = \"one\";\n    b = a;\n    p[0].key = \"two\";\n    q = p;\n    y = x;\n    struct table t;\n    struct table u;\n    t.names[1] = \"three\";\n    u.names = t.names;\n    printf(\"%s %s %d %s\\n\", b[0], q[0].key, y[0], u.names[1]);\n    return 0;\n}\n";
+        let ast = spl_parser::parse(source).unwrap();
+        let ir = emit_llvmir("test_array_copy.spl", ast.clone());
+        assert_eq!(ir, "; ModuleID = 'test_array_copy.spl'\nsource_filename = \"test_array_copy.spl\"\n\n%pair = type { ptr, i32 }\n%table = type { [2 x ptr] }\n\n@0 = internal global [4 x i8] c\"one\\00\"\n@1 = internal global [4 x i8] c\"two\\00\"\n@2 = internal global [6 x i8] c\"three\\00\"\n@3 = internal global [13 x i8] c\"%s %s %d %s\\0A\\00\"\n\ndefine i32 @main() {\nentry:\n  %mark = call i64 @spl_str_mark()\n  %a = alloca [2 x ptr], align 8\n  store [2 x ptr] zeroinitializer, ptr %a, align 8\n  %b = alloca [2 x ptr], align 8\n  store [2 x ptr] zeroinitializer, ptr %b, align 8\n  %p = alloca [2 x %pair], align 8\n  store [2 x %pair] zeroinitializer, ptr %p, align 8\n  %field = getelementptr inbounds [2 x %pair], ptr %p, i32 0, i32 0\n  %field1 = getelementptr inbounds %pair, ptr %field, i32 0, i32 0\n  %q = alloca [2 x %pair], align 8\n  store [2 x %pair] zeroinitializer, ptr %q, align 8\n  %field2 = getelementptr inbounds [2 x %pair], ptr %q, i32 0, i32 0\n  %field3 = getelementptr inbounds %pair, ptr %field2, i32 0, i32 0\n  %x = alloca [3 x i32], align 4\n  %y = alloca [3 x i32], align 4\n  %0 = call ptr @spl_str_new(ptr @0)\n  %index = getelementptr inbounds [2 x ptr], ptr %a, i32 0, i32 0\n  call void @spl_str_assign(ptr %index, ptr %0)\n  call void @spl_str_drain(i64 %mark)\n  call void @spl_str_retain_fields(ptr %a, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %b, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %b, ptr align 1 %a, i64 ptrtoint (ptr getelementptr ([2 x ptr], ptr null, i32 1) to i64), i1 false)\n  %1 = call ptr @spl_str_new(ptr @1)\n  %index4 = getelementptr inbounds [2 x %pair], ptr %p, i32 0, i32 0\n  %key = getelementptr inbounds %pair, ptr %index4, i32 0, i32 0\n  call void @spl_str_assign(ptr %key, ptr %1)\n  call void @spl_str_drain(i64 %mark)\n  %field5 = getelementptr inbounds [2 x %pair], ptr %p, i32 0, i32 0\n  %field6 = getelementptr inbounds %pair, ptr %field5, i32 0, i32 0\n  call void @spl_str_retain_fields(ptr %field6, i64 2, i64 ptrtoint (ptr getelementptr (%pair, ptr null, i32 1) to i64))\n  %field7 = getelementptr inbounds [2 x %pair], ptr %q, i32 0, i32 0\n  %field8 = getelementptr inbounds %pair, ptr %field7, i32 0, i32 0\n  call void @spl_str_release_fields(ptr %field8, i64 2, i64 ptrtoint (ptr getelementptr (%pair, ptr null, i32 1) to i64))\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %q, ptr align 1 %p, i64 ptrtoint (ptr getelementptr ([2 x %pair], ptr null, i32 1) to i64), i1 false)\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %y, ptr align 1 %x, i64 ptrtoint (ptr getelementptr ([3 x i32], ptr null, i32 1) to i64), i1 false)\n  %t = alloca %table, align 8\n  store %table zeroinitializer, ptr %t, align 8\n  %field9 = getelementptr inbounds %table, ptr %t, i32 0, i32 0\n  %u = alloca %table, align 8\n  store %table zeroinitializer, ptr %u, align 8\n  %field10 = getelementptr inbounds %table, ptr %u, i32 0, i32 0\n  %2 = call ptr @spl_str_new(ptr @2)\n  %names = getelementptr inbounds %table, ptr %t, i32 0, i32 0\n  %index11 = getelementptr inbounds [2 x ptr], ptr %names, i32 0, i32 1\n  call void @spl_str_assign(ptr %index11, ptr %2)\n  call void @spl_str_drain(i64 %mark)\n  %names12 = getelementptr inbounds %table, ptr %t, i32 0, i32 0\n  %names13 = getelementptr inbounds %table, ptr %u, i32 0, i32 0\n  call void @spl_str_retain_fields(ptr %names12, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %names13, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @llvm.memcpy.p0.p0.i64(ptr align 1 %names13, ptr align 1 %names12, i64 ptrtoint (ptr getelementptr ([2 x ptr], ptr null, i32 1) to i64), i1 false)\n  %index14 = getelementptr inbounds [2 x ptr], ptr %b, i32 0, i32 0\n  %b15 = load ptr, ptr %index14, align 8\n  %3 = call ptr @spl_str_cstr(ptr %b15)\n  %index16 = getelementptr inbounds [2 x %pair], ptr %q, i32 0, i32 0\n  %key17 = getelementptr inbounds %pair, ptr %index16, i32 0, i32 0\n  %q.key = load ptr, ptr %key17, align 8\n  %4 = call ptr @spl_str_cstr(ptr %q.key)\n  %index18 = getelementptr inbounds [3 x i32], ptr %y, i32 0, i32 0\n  %y19 = load i32, ptr %index18, align 4\n  %names20 = getelementptr inbounds %table, ptr %u, i32 0, i32 0\n  %index21 = getelementptr inbounds [2 x ptr], ptr %names20, i32 0, i32 1\n  %u.names = load ptr, ptr %index21, align 8\n  %5 = call ptr @spl_str_cstr(ptr %u.names)\n  %6 = call i32 (ptr, ...) @printf(ptr @3, ptr %3, ptr %4, i32 %y19, ptr %5)\n  call void @spl_str_release(ptr %a, i64 2)\n  call void @spl_str_release(ptr %b, i64 2)\n  call void @spl_str_release_fields(ptr %field1, i64 2, i64 ptrtoint (ptr getelementptr (%pair, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %field3, i64 2, i64 ptrtoint (ptr getelementptr (%pair, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %field9, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  call void @spl_str_release_fields(ptr %field10, i64 2, i64 ptrtoint (ptr getelementptr (ptr, ptr null, i32 1) to i64))\n  ret i32 0\n}\n\ndeclare ptr @spl_str_new(ptr)\n\ndeclare void @spl_str_assign(ptr, ptr)\n\ndeclare i64 @spl_str_mark()\n\ndeclare void @spl_str_drain(i64)\n\ndeclare void @spl_str_retain_fields(ptr, i64, i64)\n\ndeclare void @spl_str_release_fields(ptr, i64, i64)\n\n; Function Attrs: nocallback nofree nounwind willreturn memory(argmem: readwrite)\ndeclare void @llvm.memcpy.p0.p0.i64(ptr noalias nocapture writeonly, ptr noalias nocapture readonly, i64, i1 immarg) #0\n\ndeclare ptr @spl_str_cstr(ptr)\n\ndeclare i32 @printf(ptr, ...)\n\ndeclare void @spl_str_release(ptr, i64)\n\nattributes #0 = { nocallback nofree nounwind willreturn memory(argmem: readwrite) }\n");
+    }
+
     #[test]
     fn gen_test_r00() {
         test_from_file("../../test/test_0_r00.spl", "../../test/test_0_r00.ll", true, false);
@@ -146,6 +234,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_typed_self_define() {
+        for i in 3..=5 {
+            let source_path = format!("../../test/phase3/self_def_s{:02}.spl", i);
+            let expected_path = format!("../../test/phase3/self_def_s{:02}.ll", i);
+            test_typed_from_file(&source_path, &expected_path);
+        }
+    }
+
     #[test]
     fn test_phase3() {
         for i in 1..=6 {
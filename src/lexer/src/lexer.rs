@@ -24,9 +24,11 @@ impl<'input> Iterator for Lexer<'input> {
         .map(|(token, span)|
         match token {
             Ok(token) => Ok((span.start, token, span.end)),
-            Err(LexicalError::UnknownToken) => Ok((span.start, Token::Error, span.end)),
-            Err(LexicalError::InvalidCharacter(_)) => Ok((span.start, Token::Error, span.end)),
-            _ => Ok((span.start, Token::Error, span.end)),
+            // Malformed numbers keep their message, other lexemes are unknown
+            Err(LexicalError::InvalidInteger(message)) => Ok((span.start, Token::Error(Some(message)), span.end)),
+            Err(LexicalError::UnknownToken) => Ok((span.start, Token::Error(None), span.end)),
+            Err(LexicalError::InvalidCharacter(_)) => Ok((span.start, Token::Error(None), span.end)),
+            _ => Ok((span.start, Token::Error(None), span.end)),
         })
     }
 }  
@@ -129,7 +129,7 @@ mod test {
     fn literals() {
         assert_lex(
             r#"
-                true false 0 42 0xDEAD 0Xdead 3.14 .12345 500.1 10.000 'f' "doge to the moon"
+                true false 0 42 0xDEAD 0Xdead 7u 8L 9ul 0xFFLU 3.14 .12345 500.1 10.000 0.1 'f' "doge to the moon"
             "#,
              &[
                 (LiteralBool(true), "true"),
@@ -138,10 +138,15 @@ mod test {
                 (LiteralInt(42), "42"),
                 (LiteralInt(0xDEAD), "0xDEAD"),
                 (LiteralInt(0xdead), "0Xdead"),
+                (LiteralSizedInt((7, 32, false)), "7u"),
+                (LiteralSizedInt((8, 64, true)), "8L"),
+                (LiteralSizedInt((9, 64, false)), "9ul"),
+                (LiteralSizedInt((0xFF, 64, false)), "0xFFLU"),
                 (LiteralFloat(3.14), "3.14"),
                 (LiteralFloat(0.12345), ".12345"),
                 (LiteralFloat(500.1), "500.1"),
                 (LiteralFloat(10.000), "10.000"),
+                (LiteralFloat(0.1), "0.1"),
                 (LiteralChar(char::from('f')), "'f'"),
                 (LiteralString(String::from("doge to the moon")), r#""doge to the moon""#),
             ][..]
@@ -161,11 +166,64 @@ mod test {
         ])
     }
 
+    #[test]
+    fn radix_literals() {
+        assert_lex(
+            "0o755 0b1010 1_000_000 0xFF_FF 0b1_0u 0o17L",
+            &[
+                (LiteralInt(0o755), "0o755"),
+                (LiteralInt(0b1010), "0b1010"),
+                (LiteralInt(1_000_000), "1_000_000"),
+                (LiteralInt(0xFFFF), "0xFF_FF"),
+                (LiteralSizedInt((2, 32, false)), "0b1_0u"),
+                (LiteralSizedInt((0o17, 64, true)), "0o17L"),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn invalid_integers() {
+        use crate::tokens::LexicalError::InvalidInteger;
+        let cases = [
+            ("0b102", "Invalid digit '2' for base 2 in 0b102"),
+            ("0o78", "Invalid digit '8' for base 8 in 0o78"),
+            ("0x1G", "Invalid digit 'G' for base 16 in 0x1G"),
+            ("0x", "Integer literal 0x has no digits"),
+            ("1_", "Misplaced digit separator in 1_"),
+            ("0755", "Leading zeros are not allowed in 0755, octal numbers start with 0o"),
+            ("9223372036854775808", "Integer literal 9223372036854775808 is out of range, expected a value between 0 and 9223372036854775807"),
+            ("9223372036854775808L", "Integer literal 9223372036854775808L is out of range, expected a value between 0 and 9223372036854775807"),
+            ("0x1_0000_0000u", "Integer literal 0x1_0000_0000u is out of range, expected a value between 0 and 4294967295"),
+        ];
+        for (source, message) in cases {
+            let mut lexer = Token::lexer(source);
+            assert_eq!(lexer.next(), Some(Err(InvalidInteger(message.to_owned()))), "{}", source);
+            assert_eq!(lexer.next(), None);
+        }
+    }
+
+    #[test]
+    fn chars() {
+        assert_lex(r#"
+            'a' '\n' '\t' '\\' '\'' '\0' '\x41' '"'
+        "#,
+        &[
+            (LiteralChar('a'), "'a'"),
+            (LiteralChar('\n'), r"'\n'"),
+            (LiteralChar('\t'), r"'\t'"),
+            (LiteralChar('\\'), r"'\\'"),
+            (LiteralChar('\''), r"'\''"),
+            (LiteralChar('\0'), r"'\0'"),
+            (LiteralChar('A'), r"'\x41'"),
+            (LiteralChar('"'), "'\"'"),
+        ])
+    }
+
     #[test]
     fn keywords() {
         assert_lex(
             "
-                else for if return while continue break
+                else for if return while continue break new delete sizeof const
             ",
              &[
                 (KeywordElse, "else"),
@@ -174,7 +232,11 @@ mod test {
                 (KeywordReturn, "return"),
                 (KeywordWhile, "while"),
                 (KeywordContinue, "continue"),
-                (KeywordBreak, "break")
+                (KeywordBreak, "break"),
+                (KeywordNew, "new"),
+                (KeywordDelete, "delete"),
+                (KeywordSizeof, "sizeof"),
+                (KeywordConst, "const")
             ][..]
         );
     }
@@ -197,7 +259,7 @@ mod test {
     fn operators() {
         assert_lex(
             "
-                ++ -- ! * / % ^ + - < <= > >= == != && || = & |
+                ++ -- ! * / % ^ + - < <= > >= == != && || = & | ->
             ",
              &[
                 (OpIncrement, "++"),
@@ -220,6 +282,7 @@ mod test {
                 (OpAssign, "="),
                 (OpBitwiseAnd, "&"),
                 (OpBitwiseOr, "|"),
+                (OpArrow, "->"),
             ][..]
         );
     }
@@ -228,14 +291,21 @@ mod test {
     fn types_easy() {
         assert_lex(
             "
-                int string char float null
+                int string char float double null long short byte unsigned auto let
             ",
              &[
                 (TypeInt, "int"),
                 (TypeString, "string"),
                 (TypeChar, "char"),
                 (TypeFloat, "float"),
+                (TypeDouble, "double"),
                 (TypeNull, "null"),
+                (TypeLong, "long"),
+                (TypeShort, "short"),
+                (TypeByte, "byte"),
+                (TypeUnsigned, "unsigned"),
+                (TypeAuto, "auto"),
+                (TypeAuto, "let"),
             ][..]
         );
     }
@@ -2,12 +2,12 @@
     Supported Tokens:
 
     - EndOfProgram
-    - Operators: >, <, <=, >=, ==, !=, =, +, -, *, /, %, ^, &&, ||, !, ++, -- (Start with Op)
+    - Operators: >, <, <=, >=, ==, !=, =, +, -, *, /, %, ^, &&, ||, !, ++, --, -> (Start with Op)
     - Punctuation: ., ,, :, ;, [, ], (, ), {, } 
-    - Keywords: if, else, while, for, return, break, continue (Start with Keyword)
+    - Keywords: if, else, while, for, return, break, continue, new, delete, sizeof, const (Start with Keyword)
     - Declaration: enum, struct, fn (Start with Declaration)
-    - Type: bool, char, string, int, float, null (Start with Type)
-    - Literals: bool, float, int, char, string (Start with Literal)
+    - Type: bool, char, string, int, long, short, byte, unsigned, float, double, null (Start with Type)
+    - Literals: bool, float, int (decimal, 0x, 0o or 0b, with _ separators), char, string (Start with Literal)
     - Identifier
     - LineComment: //...
     - BlockComment: /*...*/
@@ -38,10 +38,23 @@ pub enum LexicalError {
     MissingLexeme(Span, String),
     StatementError(Span, String),
     UnknownLexeme(Span),
+    // a lexeme the lexer rejected with a message, like a malformed number
+    InvalidLexeme(Span, String),
     #[default]
     UnknownToken
 }
 
+impl LexicalError {
+    /// Error of a lexeme that could not be turned into a token, the
+    /// message is the reason given by the lexer if there is one.
+    pub fn lexeme(span: Span, message: Option<String>) -> Self {
+        match message {
+            Some(message) => LexicalError::InvalidLexeme(span, message),
+            None => LexicalError::UnknownLexeme(span),
+        }
+    }
+}
+
 /// Error type returned by calling `lex.slice().parse()` to u8.
 impl From<ParseIntError> for LexicalError {
     fn from(err: ParseIntError) -> Self {
@@ -100,6 +113,8 @@ pub enum Token {
     OpIncrement,
     #[token("--")]
     OpDecrement,
+    #[token("->")]
+    OpArrow,
     #[token("&", priority = 3)]
     OpBitwiseAnd,
     #[token("|", priority = 3)]
@@ -142,6 +157,14 @@ pub enum Token {
     KeywordBreak,
     #[token("continue")]
     KeywordContinue,
+    #[token("new")]
+    KeywordNew,
+    #[token("delete")]
+    KeywordDelete,
+    #[token("sizeof")]
+    KeywordSizeof,
+    #[token("const")]
+    KeywordConst,
 
     // Declaration
     #[token("enum")]
@@ -160,25 +183,47 @@ pub enum Token {
     TypeString,
     #[token("int")]
     TypeInt,
+    #[token("long")]
+    TypeLong,
+    #[token("short")]
+    TypeShort,
+    #[token("byte")]
+    TypeByte,
+    #[token("unsigned")]
+    TypeUnsigned,
     #[token("float")]
     TypeFloat,
+    #[token("double")]
+    TypeDouble,
     #[token("null")]
     TypeNull,
     #[token("void")]
     TypeVoid,
+    // Declared type taken from the initializer
+    #[token("auto")]
+    #[token("let")]
+    TypeAuto,
 
     // Literals
     #[regex("true|false", |lex| lex.slice() == "true")]
     LiteralBool(bool),
-    #[regex(r"(?:0|[1-9]\d*)?\.\d+(?:[eE][+-]?\d+)?", |lex| lex.slice().parse::<f32>().unwrap(), priority = 10)]
-    LiteralFloat(f32),
-    #[regex(r"(0|[1-9][0-9]*)", |lex| lex.slice().parse::<u32>().unwrap())]
-    #[regex(r"0[xX][0-9a-fA-F]+", process_hex)]
-    LiteralInt(u32),
+    // Parsed in double precision, narrowed only where a float is needed
+    #[regex(r"(?:0|[1-9]\d*)?\.\d+(?:[eE][+-]?\d+)?", |lex| lex.slice().parse::<f64>().unwrap(), priority = 10)]
+    LiteralFloat(f64),
+    // Decimal, `0x` hexadecimal, `0o` octal or `0b` binary, digits may be separated by `_`.
+    // Any letter after a prefix is matched, so that a bad digit is reported as such
+    // Any value of a `long` is accepted, the parser types it as a `long` if it needs one
+    #[regex(r"[0-9][0-9_]*", process_int, priority = 5)]
+    #[regex(r"0[xXoObB][0-9a-zA-Z_]*", process_int, priority = 5)]
+    LiteralInt(u64),
+    // Literals with a `u`, `l` or `ul` suffix: (value, bits, signed)
+    #[regex(r"[0-9][0-9_]*([uU][lL]?|[lL][uU]?)", process_sized_int, priority = 10)]
+    #[regex(r"0[xXoObB][0-9a-fA-F_]+([uU][lL]?|[lL][uU]?)", process_sized_int, priority = 10)]
+    LiteralSizedInt((u64, u32, bool)),
 
     #[token("'", process_char)]
     LiteralChar(char),
-    #[regex(r#""([^"\\]|\\["\\bnfrt]|\\x[0-9a-fA-F]{2}|\\u[a-fA-F0-9]{1,6})*""#, process_string)]
+    #[regex(r#""([^"\\]|\\["'\\bnfrt0]|\\x[0-9a-fA-F]{2}|\\u[a-fA-F0-9]{1,6})*""#, process_string)]
     LiteralString(String),
 
     // Identifier
@@ -193,32 +238,103 @@ pub enum Token {
     // error handling
     #[regex(r"((0|[1-9][0-9]*)[a-zA-Z_][a-zA-Z0-9_]*)|(0[0-9]+(\.[0-9]+)?)")]
     Invalid,
-    Error
+    // Message of the lexer if it rejected the lexeme with one
+    Error(Option<String>)
 }
 
 fn process_char(lex: &mut logos::Lexer<Token>) -> Result<char, LexicalError> {
-    if let Some(len) = lex.remainder().find("'")  {
+    // The closing quote is searched after an escaped character, so that '\'' is a quote
+    let remainder = lex.remainder();
+    let start = if remainder.starts_with('\\') { 2 } else { 0 };
+    if let Some(len) = remainder.get(start..).and_then(|rest| rest.find('\'')).map(|i| i + start) {
         lex.bump(len + 1);
         let slice = &lex.slice()[1..len + 1];
-        if len == 1 {
-            Ok(slice.chars().next().unwrap())
-        } else if &slice[..2] == "\\x" {
-            match u8::from_str_radix(&slice[2..], 16) {
-                Ok(byte) => Ok(byte as char),
-                Err(_) => Err(LexicalError::InvalidCharacter(format!("Invalid hexadecimal character: {}", slice))),
-            }
-        } else {
-            Err(LexicalError::InvalidCharacter(format!("Invalid character: {}", slice)))
+        let mut chars = slice.chars();
+        let c = match chars.next() {
+            Some('\\') => unescape(&mut chars),
+            c => c,
+        };
+        match (c, chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ if slice.starts_with("\\x") => Err(LexicalError::InvalidCharacter(format!("Invalid hexadecimal character: {}", slice))),
+            _ => Err(LexicalError::InvalidCharacter(format!("Invalid character: {}", slice))),
         }
     } else {
         Err(LexicalError::UnexpectedEndOfProgram)
     }
 }
 
-fn process_hex(lex: &mut logos::Lexer<Token>) -> Result<u32, LexicalError> {
+// Character of an escape sequence whose backslash is consumed already,
+// shared by character and string literals
+fn unescape(chars: &mut impl Iterator<Item = char>) -> Option<char> {
+    match chars.next()? {
+        'x' => {
+            // Parse hexadecimal byte (\xNN)
+            let hex = chars.by_ref().take(2).collect::<String>();
+            u8::from_str_radix(&hex, 16).ok().map(|byte| byte as char)
+        }
+        'u' => {
+            // Parse Unicode escape (\u{NNNN})
+            if chars.next() != Some('{') {
+                return None;
+            }
+            let unicode = chars.by_ref().take_while(|&c| c != '}').collect::<String>();
+            u32::from_str_radix(&unicode, 16).ok().and_then(char::from_u32)
+        }
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        'b' => Some('\x08'), // Backspace
+        'f' => Some('\x0C'), // Form feed
+        '0' => Some('\0'),
+        other => Some(other), // Any other escaped character
+    }
+}
+
+// Value of an integer literal whose suffix is stripped already, which
+// has to be at most `max`
+fn parse_integer(literal: &str, digits: &str, max: u64) -> Result<u64, LexicalError> {
+    let error = |message: String| Err(LexicalError::InvalidInteger(message));
+    let (radix, digits) = match digits.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits),
+    };
+    if let Some(c) = digits.chars().find(|c| *c != '_' && !c.is_digit(radix)) {
+        return error(format!("Invalid digit '{}' for base {} in {}", c, radix, literal));
+    }
+    if digits.trim_matches('_').is_empty() {
+        return error(format!("Integer literal {} has no digits", literal));
+    }
+    if digits.starts_with('_') || digits.ends_with('_') {
+        return error(format!("Misplaced digit separator in {}", literal));
+    }
+    if radix == 10 && digits.len() > 1 && digits.starts_with('0') {
+        return error(format!("Leading zeros are not allowed in {}, octal numbers start with 0o", literal));
+    }
+    let value = digits.chars().filter(|c| *c != '_').try_fold(0u64, |acc, c| {
+        acc.checked_mul(radix as u64)?.checked_add(c.to_digit(radix)? as u64)
+    });
+    match value {
+        Some(value) if value <= max => Ok(value),
+        _ => error(format!("Integer literal {} is out of range, expected a value between 0 and {}", literal, max)),
+    }
+}
+
+fn process_int(lex: &mut logos::Lexer<Token>) -> Result<u64, LexicalError> {
+    let slice = lex.slice();
+    parse_integer(slice, slice, i64::MAX as u64)
+}
+
+fn process_sized_int(lex: &mut logos::Lexer<Token>) -> Result<(u64, u32, bool), LexicalError> {
     let slice = lex.slice();
-    let hex = &slice[2..];
-    u32::from_str_radix(hex, 16).map_err(|_| LexicalError::InvalidInteger(format!("Invalid hexadecimal number: {}", hex)))
+    let digits = slice.trim_end_matches(['u', 'U', 'l', 'L']);
+    let suffix = slice[digits.len()..].to_lowercase();
+    let (bits, signed) = (if suffix.contains('l') { 64 } else { 32 }, !suffix.contains('u'));
+    // The value has to fit in the type given by the suffix
+    let max = if signed { (1u64 << (bits - 1)) - 1 } else { u64::MAX >> (64 - bits) };
+    Ok((parse_integer(slice, digits, max)?, bits, signed))
 }
 
 fn process_string(lex: &mut logos::Lexer<Token>) -> Result<String, LexicalError> {
@@ -228,32 +344,8 @@ fn process_string(lex: &mut logos::Lexer<Token>) -> Result<String, LexicalError>
     while let Some(c) = chars.next() {
         match c {
             '\\' => {
-                match chars.next() {
-                    Some('x') => {
-                        // Parse hexadecimal byte (\xNN)
-                        let hex = chars.by_ref().take(2).collect::<String>();
-                        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                            result.push(byte as char);
-                        }
-                    }
-                    Some('u') => {
-                        // Parse Unicode escape (\u{NNNN})
-                        if chars.next() == Some('{') {
-                            let unicode = chars.by_ref().take_while(|&c| c != '}').collect::<String>();
-                            if let Ok(value) = u32::from_str_radix(&unicode, 16) {
-                                if let Some(ch) = char::from_u32(value) {
-                                    result.push(ch);
-                                }
-                            }
-                        }
-                    }
-                    Some('n') => result.push('\n'),
-                    Some('r') => result.push('\r'),
-                    Some('t') => result.push('\t'),
-                    Some('b') => result.push('\x08'), // Backspace
-                    Some('f') => result.push('\x0C'), // Form feed
-                    Some(other) => result.push(other), // Any other escaped character
-                    None => break, // End of string
+                if let Some(c) = unescape(&mut chars) {
+                    result.push(c);
                 }
             }
             c => result.push(c),
@@ -292,6 +384,7 @@ impl fmt::Display for Token {
             Token::OpNot => write!(f, "!"),
             Token::OpIncrement => write!(f, "++"),
             Token::OpDecrement => write!(f, "--"),
+            Token::OpArrow => write!(f, "->"),
             _ => write!(f, "{:?}", self)
         }
     }
@@ -320,6 +413,12 @@ impl fmt::Display for LexicalError {
                 write!(f, "Error type A at Line {}: Unknown lexeme {}",
                     lineno, input[span.start..span.end].to_string().to_owned())
             },
+            LexicalError::InvalidLexeme(span, msg) => {
+                let mut input = String::new();
+                File::open(&span.source).expect("File not found").read_to_string(&mut input).expect("Error reading file");
+                let lineno = input[..span.start].lines().count();
+                write!(f, "Error type A at Line {}: {}", lineno, msg)
+            },
             LexicalError::StatementError(span, msg) => {
                 let mut input = String::new();
                 File::open(&span.source).expect("File not found").read_to_string(&mut input).expect("Error reading file");
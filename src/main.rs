@@ -11,6 +11,15 @@ fn main() -> Result<(), String> {
         .arg(Arg::new("output").short('o').long("output").required(false))
         .arg(Arg::new("debug").short('d').long("debug").required(false).action(ArgAction::SetTrue))
         .arg(Arg::new("llvm-ir").short('l').long("llvm-ir").required(false).action(ArgAction::SetTrue))
+        // Modules compiled separately from the one with `main`
+        .arg(Arg::new("lib").long("lib").required(false).action(ArgAction::SetTrue))
+        // Warnings are enabled with -W <lint> and allowed with -A <lint>, `all`
+        // names every lint and -Werror makes warnings fail the compilation
+        .arg(Arg::new("warn").short('W').value_name("lint").action(ArgAction::Append))
+        .arg(Arg::new("allow").short('A').value_name("lint").action(ArgAction::Append))
+        // Structs of different names may be assigned to each other if their fields match
+        .arg(Arg::new("struct-equivalence").long("struct-equivalence").value_name("mode")
+            .value_parser(["name", "structural"]).default_value("name"))
         .get_matches();
 
     let source_path = args.get_one::<String>("input").unwrap();
@@ -29,12 +38,28 @@ fn main() -> Result<(), String> {
         println!("{:#?}", ast);
     }
 
-    let mut walker = Walker::new(ast.clone(), &source_path, args.get_flag("debug"));
+    let mut walker = Walker::new(ast, source_path, args.get_flag("debug"));
+    // Later flags win, as in `-A all -W unused-variable`
+    let mut lints = Vec::new();
+    for (id, enabled) in [("warn", true), ("allow", false)] {
+        if let (Some(indices), Some(names)) = (args.indices_of(id), args.get_many::<String>(id)) {
+            lints.extend(indices.zip(names).map(|(index, name)| (index, name, enabled)));
+        }
+    }
+    lints.sort();
+    for (_, name, enabled) in lints {
+        walker.set_lint(name, enabled)?;
+    }
+    walker.set_lib(args.get_flag("lib"));
+    walker.set_struct_equivalence(args.get_one::<String>("struct-equivalence").unwrap())?;
     walker.traverse();
+    walker.print_warnings();
     let errors = walker.print_errors();
     if errors.is_err() {
         return Err(errors.unwrap_err());
     }
+    // The code generator needs the types the analyser inferred
+    let ast = walker.typed_program();
 
     if args.get_flag("llvm-ir") {
         let default_output = format!("{}", source_path.replace(".spl", ".ll"));
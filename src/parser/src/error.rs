@@ -15,6 +15,9 @@ impl EmitError for LexicalError {
             LexicalError::UnknownLexeme(span) => {
                 (span, format!("{} Unknown lexeme [{}]", "error:".red(), "A".red()))
             },
+            LexicalError::InvalidLexeme(span, msg) => {
+                (span, format!("{} {} [{}]", "error:".red(), msg, "A".red()))
+            },
             LexicalError::MissingLexeme(span, token) => {
                 (span, format!("{} missing {} [{}]", "error:".red(), token.as_str(), "B".red()))
             },
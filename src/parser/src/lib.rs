@@ -151,6 +151,9 @@ mod tests {
         // Test parameter declaration
         assert_parse(Parser::ParaDecsParser, "int a, int b",
         "Formal Parameter: a = [0: u32] with dimensions [], Formal Parameter: b = [0: u32] with dimensions []");
+        // Test array parameters, the outermost length may be omitted
+        assert_parse(Parser::ParaDecsParser, "int a[], float m[][3], char s[8]",
+        "Formal Parameter: a = [0: u32] with dimensions [0], Formal Parameter: m = [0: f32] with dimensions [0, 3], Formal Parameter: s = [ : char] with dimensions [8]");
     }
 
     #[test]
@@ -180,6 +183,9 @@ mod tests {
         // Test break and continue
         assert_parse(Parser::FuncDecParser, "int func(int a, int b) { while(a > b) { if (a == 5) { break; } continue; }}",
         "Function: func:[Body: [While Loop (Condition: a > b):\ndo Body: [If: Condition: a == 5: u32 then Body: [Break], Continue]]]");
+        // Test labeled loops
+        assert_parse(Parser::FuncDecParser, "int func(int a, int b) { outer: while(a > b) { while(b > 0) { break outer; } continue outer; }}",
+        "Function: func:[Body: [Label outer: While Loop (Condition: a > b):\ndo Body: [While Loop (Condition: b > 0: u32):\ndo Body: [Break outer], Continue outer]]]");
         assert_parse(Parser::FuncDecParser, "int func(int a, int b) { int c = 0; {int d = c;} }",
             "Function: func:[Body: [Variable Declaration: c = [0: u32] with dimensions []; Variable Assignment: c = 0: u32, \nNested Body: [Variable Declaration: d = [0: u32] with dimensions []; Variable Assignment: d = c]]]")
     }
@@ -205,6 +211,58 @@ mod tests {
         "Function: func:[Body: [FuncCall: add[(FuncCall: add[(a + 1: u32), b] * 2: u32), b], Return: k]]");
     }
 
+    #[test]
+    fn test_generic_func() {
+        // Test generic functions, type parameters may be returned and passed
+        assert_parse(Parser::FuncDecParser, "T max<T>(T a, T b) { if(a > b) { return a; } return b; }",
+        "Generic<T> Function: max:[Body: [If: Condition: a > b then Body: [Return: a], Return: b]]");
+        assert_parse(Parser::FuncDecParser, "int first<T, U>(T* a, U b[], int n) { return n; }",
+        "Generic<T, U> Function: first:[Body: [Return: n]]");
+    }
+
+    #[test]
+    fn test_pointer() {
+        // Test pointer declaration, arithmetic and assignment through a pointer
+        assert_parse(Parser::FuncDecParser, "int func(int **p, int *q) { *q = *(q + 1); p = null; }",
+        "Function: func:[Body: [Variable Assignment: (* q) = (* (q + 1: u32)), Variable Assignment: p = Pointer(null)]]");
+        // Test member access through a pointer
+        assert_parse(Parser::FuncDecParser, "int func(struct node *n) { n->next->val = 1; }",
+        "Function: func:[Body: [Variable Assignment: Struct Reference: [(* Struct Reference: [(* n), next]), val] = 1: u32]]");
+    }
+
+    #[test]
+    fn test_heap() {
+        // Test allocation, release and the size of types
+        assert_parse(Parser::FuncDecParser, "int func(int n) { struct node *p = new struct node; int *a = new int[n * sizeof(int*)]; delete p; }",
+        "Function: func:[Body: [Variable Declaration: p = [Pointer(Struct(node))] with dimensions []; Variable Assignment: p = New Struct(node), Variable Declaration: a = [Pointer(0: u32)] with dimensions []; Variable Assignment: a = New [0: u32; (n * SizeOf(Pointer(0: u32)))], Delete: p]]");
+    }
+
+    #[test]
+    fn test_sized_int() {
+        // Test integer types of other widths and suffixed literals
+        assert_parse(Parser::FuncDecParser, "long func(unsigned int a, byte b) { short s = -1; a = 3000000000u; return 1l + 0xffUL; }",
+        "Function: func:[Body: [Variable Declaration: s = [0: i16] with dimensions []; Variable Assignment: s = (0: u32 - 1: u32), Variable Assignment: a = 3000000000: u32, Return: (1: i64 + 255: u64)]]");
+        assert_parse(Parser::StmtParser, "unsigned long x = -2lu;",
+        "GlobalVariable: [Variable Assignment: Variable Declaration: x = [0: u64] with dimensions [] = (0: u64 - 2: u64)]");
+        // Unsuffixed literals too large for an int are longs
+        assert_parse(Parser::CompExprParser, "2147483647 + 2147483648", "(2147483647: u32 + 2147483648: i64)");
+        assert_parse(Parser::CompExprParser, "5000000000 * 0x7fff_ffff_ffff_ffff", "(5000000000: i64 * 9223372036854775807: i64)");
+    }
+
+    #[test]
+    fn test_double() {
+        // Test double declarations, literals keep their precision
+        assert_parse(Parser::FuncDecParser, "double func(float f) { double d = 0.1; return d * f - 1.5e-3; }",
+        "Function: func:[Body: [Variable Declaration: d = [0: f64] with dimensions []; Variable Assignment: d = 0.1: f32, Return: ((d * f) - 0.0015: f32)]]");
+    }
+
+    #[test]
+    fn test_auto() {
+        // Test declarations whose type is left to the analyser
+        assert_parse(Parser::FuncDecParser, "int func(int a) { auto b = a * 2, c; let d = func(b); }",
+        "Function: func:[Body: [Variable Declaration: b = [auto] with dimensions []; Variable Assignment: b = (a * 2: u32); Variable Declaration: c = [auto] with dimensions [], Variable Declaration: d = [auto] with dimensions []; Variable Assignment: d = FuncCall: func[b]]]");
+    }
+
     #[test]
     fn test_stmt(){
         assert_parse(Parser::StmtParser, "int a;", 
@@ -213,12 +271,19 @@ mod tests {
         "GlobalVariable: [Variable Assignment: Variable Declaration: a = [0: u32] with dimensions [] = 1: u32, Variable Assignment: Variable Declaration: b = [0: u32] with dimensions [] = 2: u32]");
         assert_parse(Parser::StmtParser, "struct obj { int a; char b; };", 
         "Struct: Struct Definition: obj with [Variable Declaration: a = [0: u32] with dimensions [], Variable Declaration: b = [ : char] with dimensions []]");
+        assert_parse(Parser::StmtParser, "struct obj;", "Struct: Struct Forward Declaration: obj");
         assert_parse(Parser::StmtParser, "#include \"../hi.h\"", "Include: ../hi.h");
         assert_parse(Parser::StmtParser, "int a[1];", "GlobalVariable: [Variable Declaration: a = [0: u32] with dimensions [1: u32]]");
         assert_parse(Parser::ProgramParser, "int main(){ int a; int *b = &a; int c = *b;}", 
         "Functions: Function: main:[Body: [Variable Declaration: a = [0: u32] with dimensions [], Variable Declaration: b = [Pointer(0: u32)] with dimensions []; Variable Assignment: b = (& a), Variable Declaration: c = [0: u32] with dimensions []; Variable Assignment: c = (* b)]]");
         assert_parse(Parser::ProgramParser, "int main(){ int a[5][3]; a[5] = {0, 1, 2}; int b[5] = {1, 2, 3, 4, 5};}",
-        "Functions: Function: main:[Body: [Variable Declaration: a = [0: u32] with dimensions [5: u32, 3: u32], Variable Assignment: a[5: u32] = 0: u32, 1: u32, 2: u32, Variable Declaration: b = [0: u32] with dimensions [5: u32]; Variable Assignment: b = 1: u32, 2: u32, 3: u32, 4: u32, 5: u32]]");
+        "Functions: Function: main:[Body: [Variable Declaration: a = [0: u32] with dimensions [5: u32, 3: u32], Variable Assignment: a[5: u32] = {0: u32, 1: u32, 2: u32}, Variable Declaration: b = [0: u32] with dimensions [5: u32]; Variable Assignment: b = {1: u32, 2: u32, 3: u32, 4: u32, 5: u32}]]");
+        assert_parse(Parser::ProgramParser, "int main(){ int a[2][3] = { {1, 2}, {3} }; struct P p = { .x = 1, .y = {} }; }",
+        "Functions: Function: main:[Body: [Variable Declaration: a = [0: u32] with dimensions [2: u32, 3: u32]; Variable Assignment: a = {{1: u32, 2: u32}, {3: u32}}, Struct Declaration: P extends p with []; Variable Assignment: p = {.x = 1: u32, .y = {}}]]");
+        assert_parse(Parser::StmtParser, "const int N = 4 * 8, M = N;",
+        "GlobalVariable: [Const Declaration: N = [0: u32] with value (4: u32 * 8: u32), Const Declaration: M = [0: u32] with value N]");
+        assert_parse(Parser::StmtParser, "struct P p[2] = { {1, 2}, { .y = 3 } };",
+        "GlobalVariable: [Variable Assignment: Struct Declaration: P extends p with [2: u32] = {{1: u32, 2: u32}, {.y = 3: u32}}]");
     }
 
     #[test]
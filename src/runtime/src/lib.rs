@@ -0,0 +1,299 @@
+/*
+    Runtime library of SPL, linked into every executable that uses strings.
+
+    Strings are immutable and reference counted, a null pointer is the empty
+    string so that zero initialized variables are valid. Every function that
+    creates a string hands its reference to a temporary pool: the compiler
+    takes a mark of the pool when a function is entered and drains the pool
+    back to that mark after each statement. Variables own a reference of their
+    own, taken by `spl_str_assign`, and release it when they go out of scope.
+*/
+
+// The pointers are only ever created by this library and handed back by
+// compiled SPL code, which has no way to forge them
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+
+pub struct SplString {
+    rc: usize,
+    // Contents followed by a NUL, so they can be passed to printf
+    bytes: Vec<u8>,
+}
+
+thread_local! {
+    static POOL: RefCell<Vec<*mut SplString>> = const { RefCell::new(Vec::new()) };
+}
+
+fn bytes<'a>(s: *const SplString) -> &'a [u8] {
+    if s.is_null() {
+        &[]
+    } else {
+        let bytes = unsafe { &(*s).bytes };
+        &bytes[..bytes.len() - 1]
+    }
+}
+
+fn text<'a>(s: *const SplString) -> &'a str {
+    std::str::from_utf8(bytes(s)).unwrap_or("")
+}
+
+// A new string whose only reference belongs to the pool
+fn pooled(mut contents: Vec<u8>) -> *mut SplString {
+    contents.push(0);
+    let s = Box::into_raw(Box::new(SplString { rc: 1, bytes: contents }));
+    POOL.with(|pool| pool.borrow_mut().push(s));
+    s
+}
+
+fn retain(s: *mut SplString) {
+    if !s.is_null() {
+        unsafe { (*s).rc += 1 };
+    }
+}
+
+fn release(s: *mut SplString) {
+    if s.is_null() {
+        return;
+    }
+    unsafe {
+        (*s).rc -= 1;
+        if (*s).rc == 0 {
+            drop(Box::from_raw(s));
+        }
+    }
+}
+
+/// String of a NUL terminated literal.
+#[no_mangle]
+pub extern "C" fn spl_str_new(literal: *const c_char) -> *mut SplString {
+    if literal.is_null() {
+        return pooled(Vec::new());
+    }
+    pooled(unsafe { CStr::from_ptr(literal) }.to_bytes().to_vec())
+}
+
+#[no_mangle]
+pub extern "C" fn spl_str_concat(lhs: *const SplString, rhs: *const SplString) -> *mut SplString {
+    pooled([bytes(lhs), bytes(rhs)].concat())
+}
+
+/// Lexicographic comparison, negative, zero or positive like strcmp.
+#[no_mangle]
+pub extern "C" fn spl_str_cmp(lhs: *const SplString, rhs: *const SplString) -> i32 {
+    bytes(lhs).cmp(bytes(rhs)) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn spl_str_len(s: *const SplString) -> i32 {
+    bytes(s).len() as i32
+}
+
+/// Character at an index, the program exits when it is out of range.
+#[no_mangle]
+pub extern "C" fn spl_str_at(s: *const SplString, index: i32) -> c_char {
+    match usize::try_from(index).ok().and_then(|i| bytes(s).get(i)) {
+        Some(c) => *c as c_char,
+        None => {
+            eprintln!("String index {} is out of range for length {}", index, bytes(s).len());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Contents as a C string, valid as long as the string is.
+#[no_mangle]
+pub extern "C" fn spl_str_cstr(s: *const SplString) -> *const c_char {
+    if s.is_null() {
+        c"".as_ptr()
+    } else {
+        unsafe { (*s).bytes.as_ptr() as *const c_char }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn spl_str_from_int(value: i32) -> *mut SplString {
+    pooled(value.to_string().into_bytes())
+}
+
+#[no_mangle]
+pub extern "C" fn spl_str_from_double(value: f64) -> *mut SplString {
+    pooled(value.to_string().into_bytes())
+}
+
+/// Integer value of the string, 0 if it is not a number.
+#[no_mangle]
+pub extern "C" fn spl_str_to_int(s: *const SplString) -> i32 {
+    text(s).trim().parse().unwrap_or(0)
+}
+
+/// Floating point value of the string, 0 if it is not a number.
+#[no_mangle]
+pub extern "C" fn spl_str_to_double(s: *const SplString) -> f64 {
+    text(s).trim().parse().unwrap_or(0.0)
+}
+
+/// Store a string into a variable, which takes a reference to the new
+/// value and gives up the one to the old value.
+#[no_mangle]
+pub extern "C" fn spl_str_assign(slot: *mut *mut SplString, s: *mut SplString) {
+    unsafe {
+        retain(s);
+        release(*slot);
+        *slot = s;
+    }
+}
+
+/// Give up the references of variables that go out of scope, `count`
+/// consecutive slots so that arrays of strings are released at once.
+#[no_mangle]
+pub extern "C" fn spl_str_release(slots: *mut *mut SplString, count: i64) {
+    for i in 0..count.max(0) as usize {
+        unsafe {
+            release(*slots.add(i));
+            *slots.add(i) = std::ptr::null_mut();
+        }
+    }
+}
+
+/// Slots of the string fields of structs, `count` slots `stride` bytes
+/// apart so that a field of every element of an array is visited at once.
+fn fields(first: *mut u8, count: i64, stride: i64) -> impl Iterator<Item = *mut *mut SplString> {
+    (0..count.max(0) as usize).map(move |i| unsafe { first.add(i * stride as usize) } as *mut *mut SplString)
+}
+
+/// Take a reference to the strings of a struct that was copied.
+#[no_mangle]
+pub extern "C" fn spl_str_retain_fields(first: *mut u8, count: i64, stride: i64) {
+    fields(first, count, stride).for_each(|slot| retain(unsafe { *slot }));
+}
+
+/// Give up the references of the strings of a struct that goes out of scope.
+#[no_mangle]
+pub extern "C" fn spl_str_release_fields(first: *mut u8, count: i64, stride: i64) {
+    for slot in fields(first, count, stride) {
+        unsafe {
+            release(*slot);
+            *slot = std::ptr::null_mut();
+        }
+    }
+}
+
+/// Hand the references of the strings of a returned struct to the pool,
+/// they stay alive until the caller drains it.
+#[no_mangle]
+pub extern "C" fn spl_str_keep_fields(first: *mut u8, count: i64, stride: i64) {
+    let strings = fields(first, count, stride).map(|slot| unsafe { *slot }).filter(|s| !s.is_null());
+    POOL.with(|pool| pool.borrow_mut().extend(strings));
+}
+
+/// Keep a string alive until the caller drains the pool, used for
+/// returned values whose variables are released on return.
+#[no_mangle]
+pub extern "C" fn spl_str_keep(s: *mut SplString) -> *mut SplString {
+    if !s.is_null() {
+        retain(s);
+        POOL.with(|pool| pool.borrow_mut().push(s));
+    }
+    s
+}
+
+#[no_mangle]
+pub extern "C" fn spl_str_mark() -> i64 {
+    POOL.with(|pool| pool.borrow().len() as i64)
+}
+
+/// Release the temporaries created since the mark.
+#[no_mangle]
+pub extern "C" fn spl_str_drain(mark: i64) {
+    let temps = POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let mark = (mark as usize).min(pool.len());
+        pool.split_off(mark)
+    });
+    temps.into_iter().for_each(release);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn new(s: &str) -> *mut SplString {
+        let literal = std::ffi::CString::new(s).unwrap();
+        spl_str_new(literal.as_ptr())
+    }
+
+    #[test]
+    fn test_operations() {
+        let mark = spl_str_mark();
+        let s = spl_str_concat(new("foo"), new("bar"));
+        assert_eq!(text(s), "foobar");
+        assert_eq!(spl_str_len(s), 6);
+        assert_eq!(spl_str_at(s, 3), b'b' as c_char);
+        assert!(spl_str_cmp(new("abc"), new("abd")) < 0);
+        assert_eq!(spl_str_cmp(new("abc"), new("abc")), 0);
+        assert_eq!(spl_str_cmp(ptr::null(), new("")), 0);
+        assert_eq!(unsafe { CStr::from_ptr(spl_str_cstr(s)) }.to_str(), Ok("foobar"));
+        spl_str_drain(mark);
+        assert_eq!(spl_str_mark(), mark);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mark = spl_str_mark();
+        assert_eq!(text(spl_str_from_int(-42)), "-42");
+        assert_eq!(text(spl_str_from_double(2.5)), "2.5");
+        assert_eq!(spl_str_to_int(new(" 17 ")), 17);
+        assert_eq!(spl_str_to_int(new("x")), 0);
+        assert_eq!(spl_str_to_double(new("0.1")), 0.1);
+        spl_str_drain(mark);
+    }
+
+    #[test]
+    fn test_ownership() {
+        let mark = spl_str_mark();
+        let mut var: *mut SplString = ptr::null_mut();
+        spl_str_assign(&mut var, new("kept"));
+        spl_str_drain(mark);
+        // The variable still owns its value after the temporaries are gone
+        assert_eq!(text(var), "kept");
+        assert_eq!(unsafe { (*var).rc }, 1);
+
+        let kept = spl_str_keep(var);
+        spl_str_release(&mut var, 1);
+        assert!(var.is_null());
+        assert_eq!(unsafe { (*kept).rc }, 1);
+        spl_str_drain(mark);
+    }
+
+    #[test]
+    fn test_fields() {
+        #[repr(C)]
+        struct Pair {
+            id: i64,
+            name: *mut SplString,
+        }
+        let mark = spl_str_mark();
+        let mut name: *mut SplString = ptr::null_mut();
+        spl_str_assign(&mut name, new("pair"));
+        spl_str_drain(mark);
+        let mut pairs = [Pair { id: 0, name }, Pair { id: 1, name: ptr::null_mut() }];
+        let first = &mut pairs[0].name as *mut _ as *mut u8;
+        let stride = std::mem::size_of::<Pair>() as i64;
+        // A copy of the array, whose references end up in the pool
+        spl_str_retain_fields(first, 2, stride);
+        assert_eq!(unsafe { (*name).rc }, 2);
+        spl_str_keep_fields(first, 2, stride);
+        assert_eq!(spl_str_mark(), mark + 1);
+        spl_str_drain(mark);
+        assert_eq!(unsafe { (*name).rc }, 1);
+
+        spl_str_retain_fields(first, 1, stride);
+        spl_str_release_fields(first, 2, stride);
+        assert!(pairs[0].name.is_null() && pairs[1].id == 1);
+        assert_eq!(unsafe { (*name).rc }, 1);
+        spl_str_release(&mut name, 1);
+    }
+}
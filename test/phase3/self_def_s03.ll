@@ -0,0 +1,58 @@
+; ModuleID = 'self_def_s03.spl'
+source_filename = "self_def_s03.spl"
+
+@0 = internal global [4 x i8] c"%f\0A\00"
+
+define i32 @main() {
+entry:
+  %wide = alloca double, align 8
+  %larger.float = call float @larger.float(float 5.000000e-01, float 1.500000e+00)
+  %casttmp = fpext float %larger.float to double
+  store double %casttmp, ptr %wide, align 8
+  %wide1 = load double, ptr %wide, align 8
+  %0 = call i32 (ptr, ...) @printf(ptr @0, double %wide1)
+  %larger.int = call i32 @larger.int(i32 2, i32 3)
+  ret i32 %larger.int
+}
+
+define float @larger.float(float %a, float %b) {
+entry:
+  %a1 = alloca float, align 4
+  store float %a, ptr %a1, align 4
+  %b2 = alloca float, align 4
+  store float %b, ptr %b2, align 4
+  %a3 = load float, ptr %a1, align 4
+  %b4 = load float, ptr %b2, align 4
+  %gttmp = fcmp ogt float %a3, %b4
+  br i1 %gttmp, label %then, label %merge
+
+then:                                             ; preds = %entry
+  %a5 = load float, ptr %a1, align 4
+  ret float %a5
+
+merge:                                            ; preds = %entry
+  %b6 = load float, ptr %b2, align 4
+  ret float %b6
+}
+
+declare i32 @printf(ptr, ...)
+
+define i32 @larger.int(i32 %a, i32 %b) {
+entry:
+  %a1 = alloca i32, align 4
+  store i32 %a, ptr %a1, align 4
+  %b2 = alloca i32, align 4
+  store i32 %b, ptr %b2, align 4
+  %a3 = load i32, ptr %a1, align 4
+  %b4 = load i32, ptr %b2, align 4
+  %gttmp = icmp sgt i32 %a3, %b4
+  br i1 %gttmp, label %then, label %merge
+
+then:                                             ; preds = %entry
+  %a5 = load i32, ptr %a1, align 4
+  ret i32 %a5
+
+merge:                                            ; preds = %entry
+  %b6 = load i32, ptr %b2, align 4
+  ret i32 %b6
+}
@@ -0,0 +1,77 @@
+; ModuleID = 'self_def_s04.spl'
+source_filename = "self_def_s04.spl"
+
+@0 = internal global [5 x i8] c"four\00"
+
+define i32 @sum.int_arr3(ptr %a) {
+entry:
+  %index = getelementptr inbounds [3 x i32], ptr %a, i32 0, i32 0
+  %a1 = load i32, ptr %index, align 4
+  %index2 = getelementptr inbounds [3 x i32], ptr %a, i32 0, i32 1
+  %a3 = load i32, ptr %index2, align 4
+  %addtmp = add i32 %a1, %a3
+  %index4 = getelementptr inbounds [3 x i32], ptr %a, i32 0, i32 2
+  %a5 = load i32, ptr %index4, align 4
+  %addtmp6 = add i32 %addtmp, %a5
+  ret i32 %addtmp6
+}
+
+define i32 @sum.int(i32 %x) {
+entry:
+  %x1 = alloca i32, align 4
+  store i32 %x, ptr %x1, align 4
+  %x2 = load i32, ptr %x1, align 4
+  ret i32 %x2
+}
+
+define i32 @pick.int(i32 %x) {
+entry:
+  %x1 = alloca i32, align 4
+  store i32 %x, ptr %x1, align 4
+  ret i32 1
+}
+
+define i32 @pick.long(i64 %x) {
+entry:
+  %x1 = alloca i64, align 8
+  store i64 %x, ptr %x1, align 4
+  ret i32 2
+}
+
+define i32 @len.int(i32 %x) {
+entry:
+  %x1 = alloca i32, align 4
+  store i32 %x, ptr %x1, align 4
+  %x2 = load i32, ptr %x1, align 4
+  ret i32 %x2
+}
+
+define i32 @main() {
+entry:
+  %three = alloca [3 x i32], align 4
+  %index = getelementptr [3 x i32], ptr %three, i32 0, i32 0
+  store i32 1, ptr %index, align 4
+  %index1 = getelementptr [3 x i32], ptr %three, i32 0, i32 1
+  store i32 2, ptr %index1, align 4
+  %index2 = getelementptr [3 x i32], ptr %three, i32 0, i32 2
+  store i32 3, ptr %index2, align 4
+  %small = alloca i16, align 2
+  store i16 2, ptr %small, align 2
+  %sum.int_arr3 = call i32 @sum.int_arr3(ptr %three)
+  %sum.int = call i32 @sum.int(i32 4)
+  %addtmp = add i32 %sum.int_arr3, %sum.int
+  %small3 = load i16, ptr %small, align 2
+  %casttmp = sext i16 %small3 to i32
+  %pick.int = call i32 @pick.int(i32 %casttmp)
+  %addtmp4 = add i32 %addtmp, %pick.int
+  %len.int = call i32 @len.int(i32 3)
+  %addtmp5 = add i32 %addtmp4, %len.int
+  %0 = call ptr @spl_str_new(ptr @0)
+  %len = call i32 @spl_str_len(ptr %0)
+  %addtmp6 = add i32 %addtmp5, %len
+  ret i32 %addtmp6
+}
+
+declare ptr @spl_str_new(ptr)
+
+declare i32 @spl_str_len(ptr)
@@ -0,0 +1,66 @@
+; ModuleID = 'self_def_s05.spl'
+source_filename = "self_def_s05.spl"
+
+@0 = internal global [17 x i8] c"%d %d %.1f %.1f\0A\00"
+
+define i32 @main() {
+entry:
+  %x = alloca i32, align 4
+  store i32 1, ptr %x, align 4
+  %y = alloca i32, align 4
+  store i32 2, ptr %y, align 4
+  %u = alloca double, align 8
+  store double 1.500000e+00, ptr %u, align 8
+  %v = alloca double, align 8
+  store double 2.500000e+00, ptr %v, align 8
+  call void @swap.int(ptr %x, ptr %y)
+  call void @swap.double(ptr %u, ptr %v)
+  %x1 = load i32, ptr %x, align 4
+  %y2 = load i32, ptr %y, align 4
+  %u3 = load double, ptr %u, align 8
+  %v4 = load double, ptr %v, align 8
+  %0 = call i32 (ptr, ...) @printf(ptr @0, i32 %x1, i32 %y2, double %u3, double %v4)
+  ret i32 0
+}
+
+define void @swap.int(ptr %a, ptr %b) {
+entry:
+  %a1 = alloca ptr, align 8
+  store ptr %a, ptr %a1, align 8
+  %b2 = alloca ptr, align 8
+  store ptr %b, ptr %b2, align 8
+  %t = alloca i32, align 4
+  %a3 = load ptr, ptr %a1, align 8
+  %deref = load i32, ptr %a3, align 4
+  store i32 %deref, ptr %t, align 4
+  %b4 = load ptr, ptr %b2, align 8
+  %deref5 = load i32, ptr %b4, align 4
+  %a6 = load ptr, ptr %a1, align 8
+  store i32 %deref5, ptr %a6, align 4
+  %t7 = load i32, ptr %t, align 4
+  %b8 = load ptr, ptr %b2, align 8
+  store i32 %t7, ptr %b8, align 4
+  ret void
+}
+
+define void @swap.double(ptr %a, ptr %b) {
+entry:
+  %a1 = alloca ptr, align 8
+  store ptr %a, ptr %a1, align 8
+  %b2 = alloca ptr, align 8
+  store ptr %b, ptr %b2, align 8
+  %t = alloca double, align 8
+  %a3 = load ptr, ptr %a1, align 8
+  %deref = load double, ptr %a3, align 8
+  store double %deref, ptr %t, align 8
+  %b4 = load ptr, ptr %b2, align 8
+  %deref5 = load double, ptr %b4, align 8
+  %a6 = load ptr, ptr %a1, align 8
+  store double %deref5, ptr %a6, align 8
+  %t7 = load double, ptr %t, align 8
+  %b8 = load ptr, ptr %b2, align 8
+  store double %t7, ptr %b8, align 8
+  ret void
+}
+
+declare i32 @printf(ptr, ...)
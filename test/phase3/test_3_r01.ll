@@ -33,7 +33,7 @@ body:                                             ; preds = %cond
   %addtmp = add i32 %multmp, %digit6
   store i32 %addtmp, ptr %reversed, align 4
   %num7 = load i32, ptr %num1, align 4
-  %divtmp = udiv i32 %num7, 10
+  %divtmp = sdiv i32 %num7, 10
   store i32 %divtmp, ptr %num1, align 4
   br label %cond
 
@@ -56,7 +56,7 @@ body:                                             ; preds = %cond
   %coefficient21 = load i32, ptr %coefficient, align 4
   %i22 = load i32, ptr %i, align 4
   %addtmp = add i32 %i22, 1
-  %divtmp = udiv i32 %coefficient21, %addtmp
+  %divtmp = sdiv i32 %coefficient21, %addtmp
   store i32 %divtmp, ptr %coefficient, align 4
   %i23 = load i32, ptr %i, align 4
   %addtmp24 = add i32 %i23, 1
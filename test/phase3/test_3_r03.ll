@@ -37,7 +37,7 @@ body4:                                            ; preds = %cond3
   %k9 = load i32, ptr %k, align 4
   %k10 = load i32, ptr %k, align 4
   %i11 = load i32, ptr %i, align 4
-  %divtmp = udiv i32 %k10, %i11
+  %divtmp = sdiv i32 %k10, %i11
   %i12 = load i32, ptr %i, align 4
   %multmp = mul i32 %divtmp, %i12
   %eqtmp = icmp eq i32 %k9, %multmp
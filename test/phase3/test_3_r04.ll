@@ -12,7 +12,7 @@ entry:
   %x3 = load i32, ptr %x1, align 4
   %x4 = load i32, ptr %x1, align 4
   %n5 = load i32, ptr %n2, align 4
-  %divtmp = udiv i32 %x4, %n5
+  %divtmp = sdiv i32 %x4, %n5
   %n6 = load i32, ptr %n2, align 4
   %multmp = mul i32 %divtmp, %n6
   %subtmp = sub i32 %x3, %multmp
@@ -32,7 +32,7 @@ entry:
 cond:                                             ; preds = %merge6, %entry
   %j2 = load i32, ptr %j, align 4
   %number3 = load i32, ptr %number1, align 4
-  %divtmp = udiv i32 %number3, 2
+  %divtmp = sdiv i32 %number3, 2
   %letmp = icmp sle i32 %j2, %divtmp
   br i1 %letmp, label %body, label %merge
 